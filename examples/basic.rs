@@ -11,7 +11,7 @@ fn main() {
 
     let mut frame_count = 0.0;
     
-    loop {
+    while viewer.is_running() {
         // Update frame positions
         frame_count += 0.016; // Increment by roughly 1/60th of a second
         
@@ -42,11 +42,13 @@ fn main() {
         ));
         let frame3 = trans3 * rot3;
             
-        // Clear previous frames and push new ones
-        viewer.clear_frames();
-        viewer.push_frame(frame1, "frame1");
-        viewer.push_frame(frame2, "frame2");
-        viewer.push_frame(frame3, "frame3");
+        // Replace the previous frames with the new ones in one step, so none is ever missing on screen
+        let frames: [(Transform, String); 3] = [
+            (frame1, "frame1".to_string()),
+            (frame2, "frame2".to_string()),
+            (frame3, "frame3".to_string()),
+        ];
+        viewer.replace_frames(&frames).unwrap();
         
         thread::sleep(time::Duration::from_millis(16)); // ~60 FPS
 
@@ -54,4 +56,7 @@ fn main() {
         println!("Frames updated: x = {}, y = {}, z = {}", 
                 frame1[(0, 3)], frame3[(1, 3)], frame2[(2, 3)]);
     }
+
+    // Let the render thread finish once the window is closed
+    viewer.wait();
 } 
\ No newline at end of file
@@ -13,9 +13,9 @@ Features:
     - Utilities for frame transformations and manipulations
 """
 
-from .frames_viewer import Viewer
+from .frames_viewer import GridPlane, Viewer
 
 from . import utils
 
-__all__ = ['Viewer', 'utils']
+__all__ = ['GridPlane', 'Viewer', 'utils']
 __version__ = '0.1.0' 
\ No newline at end of file
@@ -1,7 +1,15 @@
-from typing import Optional
+from types import TracebackType
+from typing import Dict, List, Optional, Sequence, Tuple, Type
 import numpy as np
 import numpy.typing as npt
 
+class GridPlane:
+    """A world plane the grid can be drawn in, for `Viewer.set_grid_planes`."""
+
+    XY: GridPlane
+    XZ: GridPlane
+    YZ: GridPlane
+
 class Viewer:
     """A real-time 6D frames viewer with OpenGL rendering.
 
@@ -13,11 +21,47 @@ class Viewer:
         - Real-time visualization of multiple coordinate frames
         - Metric units (meters)
         - Interactive camera controls (orbit, pan, zoom)
-        - Grid visualization in XY, XZ, and YZ planes
+        - Grid visualization on the ground plane
+
+    Used as a context manager (`with Viewer() as viewer:`), the viewer starts
+    when entering the block and closes its window when leaving it.
     """
 
-    def __init__(self) -> None:
-        """Initialize a new frames viewer."""
+    def __init__(
+        self,
+        vsync: bool = False,
+        camera_position: Optional[Sequence[float]] = None,
+        camera_target: Optional[Sequence[float]] = None,
+        always_on_top: bool = False,
+        decorations: bool = True,
+        close_on_escape: bool = True,
+        title: str = "Frames Viewer",
+        width: int = 800,
+        height: int = 600,
+        headless: bool = False,
+        msaa_samples: int = 4,
+    ) -> None:
+        """Initialize a new frames viewer.
+
+        Args:
+            vsync: Synchronize rendering with the display refresh rate.
+                Removes tearing and lowers CPU usage at the cost of latency.
+            camera_position: Initial [x, y, z] camera position in meters
+            camera_target: Initial [x, y, z] point the camera looks at
+            always_on_top: Keep the window above all other windows
+            decorations: Show the window title bar and borders
+            close_on_escape: Close the window when Escape is pressed
+            title: Window title
+            width: Initial window width in logical pixels
+            height: Initial window height in logical pixels
+            headless: Render offscreen at width x height pixels instead of opening
+                a window, e.g. to take screenshots on a CI server
+            msaa_samples: Samples per pixel used to smooth lines, a power of two,
+                or 0 to disable anti-aliasing
+
+        Raises:
+            ValueError: If msaa_samples is neither 0 nor a power of two
+        """
         ...
 
     def start(self) -> None:
@@ -39,14 +83,863 @@ class Viewer:
             name: Unique identifier for the frame
         
         Raises:
-            ValueError: If transform is not a 4x4 matrix
+            ValueError: If transform is not a finite 4x4 matrix
+        """
+        ...
+
+    def push_frame_f64(self, transform: npt.NDArray[np.float64], name: str) -> None:
+        """Push a frame given in double precision, e.g. in UTM coordinates far from the origin.
+
+        The frame is stored relative to the world origin, set to the translation of the
+        first frame pushed this way unless set with `set_world_origin`. Other geometry
+        must be given relative to that origin.
+
+        Raises:
+            ValueError: If transform is not a finite 4x4 matrix
+        """
+        ...
+
+    def set_world_origin(self, origin: Tuple[float, float, float]) -> None:
+        """Set the point that frames pushed with `push_frame_f64` are stored relative to."""
+        ...
+
+    def push_frame_ttl(self, transform: npt.NDArray[np.float32], name: str, ttl: float) -> None:
+        """Push a frame that disappears unless pushed again within ttl seconds.
+
+        Pushing the frame with push_frame makes it permanent again.
+
+        Raises:
+            ValueError: If transform is not a finite 4x4 matrix or ttl is negative
+        """
+        ...
+
+    def push_frame_rt(
+        self,
+        rotation: npt.NDArray[np.float32],
+        translation: Sequence[float],
+        name: str,
+    ) -> None:
+        """Push a frame from a 3x3 rotation matrix (float32) and an [x, y, z] translation in meters.
+
+        Raises:
+            ValueError: If rotation is not a 3x3 matrix or the pose is not finite
+        """
+        ...
+
+    def push_frame_quat(self, position: Sequence[float], quaternion: Sequence[float], name: str) -> None:
+        """Push a frame from an [x, y, z] position in meters and an [x, y, z, w] quaternion.
+
+        The quaternion is normalized before use.
+
+        Raises:
+            ValueError: If the quaternion is zero or the pose is not finite
+        """
+        ...
+
+    def push_frames(self, frames: Sequence[Tuple[npt.NDArray[np.float32], str]]) -> None:
+        """Push many (transform, name) pairs at once.
+
+        The batch is applied atomically: the viewer never draws a partially updated batch.
+
+        Raises:
+            ValueError: If any transform is not a finite 4x4 matrix, in which case no frame is pushed
+        """
+        ...
+
+    def replace_frames(self, frames: Sequence[Tuple[npt.NDArray[np.float32], str]]) -> None:
+        """Make the given (transform, name) pairs the only frames, in one step.
+
+        Unlike clear_frames followed by push_frame calls, the viewer never draws an
+        empty or half updated scene. Frames that stay keep their style.
+
+        Raises:
+            ValueError: If any transform is not a finite 4x4 matrix, in which case nothing changes
+        """
+        ...
+
+    def push_frame_scaled(
+        self, transform: npt.NDArray[np.float32], name: str, scale: float
+    ) -> None:
+        """Push a frame with axes `scale` times the default 10 centimeters.
+
+        The scale is kept when the frame is later updated with push_frame.
+
+        Raises:
+            ValueError: If transform is not a finite 4x4 matrix
+        """
+        ...
+
+    def push_frame_colored(
+        self,
+        transform: npt.NDArray[np.float32],
+        name: str,
+        colors: Sequence[Sequence[float]],
+    ) -> None:
+        """Push a frame drawn with custom RGBA colors for its X, Y and Z axes.
+
+        The colors are kept when the frame is later updated with push_frame.
+
+        Raises:
+            ValueError: If transform is not a finite 4x4 matrix
+        """
+        ...
+
+    def push_frame_interpolated(
+        self, name: str, transform: npt.NDArray[np.float32], duration: float
+    ) -> None:
+        """Move a frame smoothly from its current transform to a new one over `duration` seconds.
+
+        The translation is interpolated linearly and the rotation spherically.
+        A frame that does not exist yet is added at the target directly.
+
+        Raises:
+            ValueError: If transform is not a finite 4x4 matrix or duration is not positive
+        """
+        ...
+
+    def style_frame(
+        self,
+        name: str,
+        axis_length: float = 0.1,
+        line_width: Optional[float] = None,
+        colors: Optional[Sequence[Sequence[float]]] = None,
+        alpha: float = 1.0,
+        marker_color: Optional[Sequence[float]] = None,
+    ) -> bool:
+        """Set the appearance of a frame, kept when it is pushed again.
+
+        Args:
+            name: Name of the frame
+            axis_length: Length of the axes in meters
+            line_width: Width of the axis lines in pixels, or None for the viewer's frame line width
+            colors: RGBA colors of the X, Y and Z axes, or None for the default colors
+            alpha: Opacity multiplier
+            marker_color: RGBA color of the origin marker, black by default
+
+        Returns:
+            Whether the frame exists
+        """
+        ...
+
+    def set_frame_alpha(self, name: str, alpha: float) -> bool:
+        """Change only the opacity of a frame, clamped to [0, 1]. Returns whether the frame exists."""
+        ...
+
+    def set_frame_visible(self, name: str, visible: bool) -> bool:
+        """Hide or show a frame without removing it. Returns whether the frame exists.
+
+        The frame keeps its transform, style, trail and mesh, and pushing it again
+        does not change its visibility.
+        """
+        ...
+
+    def set_frame_always_on_top(self, name: str, on_top: bool) -> bool:
+        """Draw a frame over everything else in the scene, e.g. to highlight it. Returns whether the frame exists."""
+        ...
+
+    def set_frame_ghost(self, name: str, ghost: bool) -> bool:
+        """Show a frame faded with dashed axes, e.g. when its data goes stale.
+
+        Pushing the frame again makes it live again. Returns whether the frame exists.
+        """
+        ...
+
+    def remove_frame(self, name: str) -> bool:
+        """Remove a frame. Returns whether it existed."""
+        ...
+
+    def rename_frame(self, old: str, new: str) -> bool:
+        """Rename a frame, keeping its transform.
+
+        Returns:
+            False if `old` does not exist or `new` is already taken
+        """
+        ...
+
+    def axis_angle(
+        self, a: str, a_axis: str, b: str, b_axis: str, degrees: bool = False
+    ) -> Optional[float]:
+        """Measure the angle between an axis of frame `a` and an axis of frame `b`.
+
+        Args:
+            a: Name of the first frame
+            a_axis: Axis of the first frame ("x", "y" or "z")
+            b: Name of the second frame
+            b_axis: Axis of the second frame ("x", "y" or "z")
+            degrees: Return the angle in degrees instead of radians
+
+        Returns:
+            The angle between the axes in world coordinates, or None if either frame does not exist
+
+        Raises:
+            ValueError: If an axis is not "x", "y" or "z"
+        """
+        ...
+
+    def project_to_screen(self, name: str) -> Optional[Tuple[float, float]]:
+        """Project a frame's origin to window pixel coordinates.
+
+        Args:
+            name: Name of the frame
+
+        Returns:
+            (x, y) in pixels from the top-left corner, or None if the frame does not
+            exist or its origin is behind the camera or off-screen
+        """
+        ...
+
+    def pick_frame(self, x: float, y: float) -> Optional[str]:
+        """Name of the frame drawn closest to window pixel (x, y) within a few pixels, or None.
+
+        Frames hidden behind a mesh are not picked. Blocks until the next image is drawn,
+        and returns None if the viewer is not running.
+        """
+        ...
+
+    def clicked_frame(self) -> Optional[str]:
+        """Name of the frame selected by the last left click in the window, or None."""
+        ...
+
+    def append_trajectory_point(self, name: str, point: Sequence[float]) -> None:
+        """Append an [x, y, z] point (meters) to a trajectory, creating it if needed.
+
+        Only the new point is sent to the GPU, so growing a long path every tick stays cheap.
+        """
+        ...
+
+    def enable_trail(self, name: str, max_points: int = 500) -> None:
+        """Trace the path of a frame's origin, keeping its latest max_points positions."""
+        ...
+
+    def disable_trail(self, name: str) -> bool:
+        """Stop tracing a frame's path and clear its trail. Returns whether it was enabled."""
+        ...
+
+    def remove_trajectory(self, name: str) -> bool:
+        """Remove a trajectory. Returns whether it existed."""
+        ...
+
+    def push_text(
+        self,
+        name: str,
+        position: Sequence[float],
+        text: str,
+        size: float = 14.0,
+        color: Optional[Sequence[float]] = None,
+    ) -> None:
+        """Draw text facing the camera at a world position, replacing any text of the same name.
+
+        Args:
+            name: Unique identifier for the text
+            position: [x, y, z] position of the text's bottom-left corner in meters
+            text: Text to draw
+            size: Text height in pixels
+            color: RGBA color with components in [0, 1], black by default
+        """
+        ...
+
+    def remove_text(self, name: str) -> bool:
+        """Remove a text. Returns whether it existed."""
+        ...
+
+    def push_covariance(
+        self,
+        name: str,
+        center: Sequence[float],
+        covariance: npt.NDArray[np.float32],
+        color: Optional[Sequence[float]] = None,
+    ) -> None:
+        """Draw the 1-sigma ellipsoid of a position covariance, replacing any ellipsoid of the same name.
+
+        Args:
+            name: Unique identifier for the ellipsoid
+            center: [x, y, z] center of the ellipsoid in meters
+            covariance: Symmetric 3x3 covariance matrix (float32) in square meters
+            color: RGBA color with components in [0, 1], gray by default
+
+        Raises:
+            ValueError: If covariance is not a 3x3 matrix
+        """
+        ...
+
+    def remove_covariance(self, name: str) -> bool:
+        """Remove a covariance ellipsoid. Returns whether it existed."""
+        ...
+
+    def push_plane(
+        self,
+        name: str,
+        transform: npt.NDArray[np.float32],
+        width: float,
+        height: float,
+        color: Optional[Sequence[float]] = None,
+    ) -> None:
+        """Draw a filled rectangle centered in the XY plane of a transform, replacing any plane of the same name.
+
+        Args:
+            name: Unique identifier for the plane
+            transform: 4x4 homogeneous transformation matrix (float32)
+            width: Extent along the X axis in meters
+            height: Extent along the Y axis in meters
+            color: RGBA color with components in [0, 1], translucent gray by default
+
+        Raises:
+            ValueError: If transform is not a finite 4x4 matrix
+        """
+        ...
+
+    def remove_plane(self, name: str) -> bool:
+        """Remove a plane. Returns whether it existed."""
+        ...
+
+    def push_points(
+        self,
+        name: str,
+        points: npt.NDArray[np.float32],
+        color: Optional[Sequence[float]] = None,
+    ) -> None:
+        """Draw an Nx3 point cloud (meters), replacing any point cloud of the same name.
+
+        The color is RGBA with components in [0, 1], dark gray by default.
+
+        Raises:
+            ValueError: If points is not an Nx3 array
+        """
+        ...
+
+    def remove_points(self, name: str) -> bool:
+        """Remove a point cloud. Returns whether it existed."""
+        ...
+
+    def attach_mesh(
+        self,
+        frame_name: str,
+        vertices: npt.NDArray[np.float32],
+        indices: npt.NDArray[np.uint32],
+        color: Optional[Sequence[float]] = None,
+    ) -> None:
+        """Draw a triangle mesh with the transform of a frame, replacing any mesh attached to it.
+
+        Args:
+            frame_name: Name of the frame the mesh moves with
+            vertices: Nx3 array (float32) of positions in the frame's coordinates, in meters
+            indices: Mx3 array (uint32) of vertex indices, one row per triangle
+            color: RGBA color with components in [0, 1], light gray by default
+
+        Raises:
+            ValueError: If vertices or indices are not Nx3 arrays, or an index is out of bounds
+        """
+        ...
+
+    def attach_obj(self, frame_name: str, path: str, color: Optional[Sequence[float]] = None) -> None:
+        """Load a Wavefront OBJ file and draw it with the transform of a frame, ignoring materials.
+
+        Raises:
+            RuntimeError: If the file cannot be read or parsed
+        """
+        ...
+
+    def detach_mesh(self, frame_name: str) -> bool:
+        """Remove the mesh attached to a frame. Returns whether there was one."""
+        ...
+
+    def push_lines(
+        self,
+        name: str,
+        segments: npt.NDArray[np.float32],
+        color: Optional[Sequence[float]] = None,
+    ) -> None:
+        """Draw Nx2x3 line segments (start and end in meters), replacing any segments of the same name.
+
+        The color is RGBA with components in [0, 1], dark gray by default.
+
+        Raises:
+            ValueError: If segments is not an Nx2x3 array
+        """
+        ...
+
+    def remove_lines(self, name: str) -> bool:
+        """Remove a set of line segments. Returns whether it existed."""
+        ...
+
+    def set_grid(self, size: float, step: float) -> None:
+        """Resize the world grid to `size` meters with lines every `step` meters (default 1.0 and 0.1).
+
+        Raises:
+            ValueError: Unless size and step are finite and positive, with at most
+                10000 lines along each axis
+        """
+        ...
+
+    def set_grid_visible(self, visible: bool) -> None:
+        """Show or hide the world grid."""
+        ...
+
+    def set_grid_planes(self, planes: Sequence[GridPlane]) -> None:
+        """Draw the world grid in the given planes only, e.g. [GridPlane.XY] for just the floor."""
+        ...
+
+    def set_grid_style(self, style: str) -> None:
+        """Draw the world grid with "solid" or "dashed" lines.
+
+        Raises:
+            ValueError: If the style is not "solid" or "dashed"
+        """
+        ...
+
+    def add_grid(
+        self,
+        name: str,
+        transform: npt.NDArray[np.float32],
+        size: float = 1.0,
+        step: float = 0.1,
+    ) -> None:
+        """Draw an additional grid anchored at a transform, replacing any grid of the same name.
+
+        Args:
+            name: Unique identifier for the grid
+            transform: 4x4 homogeneous transformation matrix (float32)
+            size: Extent of the grid in meters
+            step: Spacing between grid lines in meters
+
+        Raises:
+            ValueError: If transform is not a finite 4x4 matrix, or size and step
+                are not finite and positive with at most 10000 lines along each axis
+        """
+        ...
+
+    def remove_grid(self, name: str) -> bool:
+        """Remove an anchored grid. Returns whether it existed."""
+        ...
+
+    def frame_names(self) -> List[str]:
+        """Names of the frames currently in the viewer, in no particular order."""
+        ...
+
+    def get_frame(self, name: str) -> Optional[npt.NDArray[np.float32]]:
+        """Current 4x4 transform of a frame, or None if it does not exist."""
+        ...
+
+    def frame_age(self, name: str) -> Optional[float]:
+        """Seconds since the frame was last pushed, or None if it does not exist."""
+        ...
+
+    def set_age_fade(self, half_life: Optional[float]) -> None:
+        """Fade frames out as they age, halving their opacity every `half_life` seconds.
+
+        Args:
+            half_life: Half-life in seconds, or None to disable fading
+
+        Raises:
+            ValueError: If half_life is not positive
+        """
+        ...
+
+    def set_stale_hide(self, threshold: Optional[float]) -> None:
+        """Hide frames that have not been pushed for longer than `threshold` seconds.
+
+        Hidden frames are kept and show up again as soon as they are pushed.
+
+        Args:
+            threshold: Age in seconds after which frames are hidden, or None to show all frames
+
+        Raises:
+            ValueError: If threshold is not positive
+        """
+        ...
+
+    def set_update_pulse(self, duration: Optional[float]) -> None:
+        """Briefly enlarge a frame's axes each time it is pushed.
+
+        The axes are drawn at twice their length right after an update and
+        shrink back to normal over `duration` seconds.
+
+        Args:
+            duration: Pulse duration in seconds, or None to disable it
+
+        Raises:
+            ValueError: If duration is not positive
         """
         ...
 
     def clear_frames(self) -> None:
-        """Remove all frames from the viewer."""
+        """Remove all frames from the viewer, leaving point clouds, lines, meshes and other overlays."""
+        ...
+
+    def clear_points(self) -> None:
+        """Remove every point cloud."""
+        ...
+
+    def clear_lines(self) -> None:
+        """Remove every set of line segments."""
+        ...
+
+    def clear_meshes(self) -> None:
+        """Detach every mesh from its frame."""
+        ...
+
+    def clear_all(self) -> None:
+        """Remove frames and everything drawn alongside them, keeping the world grid and settings."""
+        ...
+
+    def save_trajectory_tum(self, path: str, name_prefix: str = "") -> None:
+        """Save frames whose name starts with name_prefix as a TUM trajectory file.
+
+        Each frame becomes a `timestamp tx ty tz qx qy qz qw` line, ordered by
+        the time it was last pushed.
+        """
+        ...
+
+    def screenshot(self, path: str) -> None:
+        """Save the next rendered image as a PNG file, blocking until it is written.
+
+        Raises:
+            RuntimeError: If the viewer is not running or the file cannot be written
+        """
+        ...
+
+    def wait_for_close(self, timeout: Optional[float] = None) -> bool:
+        """Block until the viewer stops, e.g. because the user closed the window.
+
+        Returns False if the timeout (in seconds) elapsed first.
+        """
+        ...
+
+    def start_recording(self, path: str, fps: float = 30.0) -> None:
+        """Start recording the rendered images to a video file, e.g. .mp4 or .gif.
+
+        Requires ffmpeg on the PATH. The video runs at fps whatever the render rate.
+
+        Raises:
+            RuntimeError: If fps is not positive, or a previous recording failed
+        """
+        ...
+
+    def stop_recording(self) -> None:
+        """Stop recording and wait until the video file is written.
+
+        Raises:
+            RuntimeError: If the video could not be recorded
+        """
+        ...
+
+    def load_sequence(
+        self, frames_over_time: List[Tuple[float, Dict[str, npt.NDArray[np.float32]]]]
+    ) -> None:
+        """Load a recorded sequence of frame sets for playback.
+
+        Playback starts paused on the first sample. Use `play`, `pause`,
+        `seek` and `set_speed` to control it.
+
+        Args:
+            frames_over_time: Samples as (timestamp in seconds, {name: 4x4 transform}) pairs
+
+        Raises:
+            ValueError: If any transform is not a finite 4x4 matrix
+        """
+        ...
+
+    def play(self) -> None:
+        """Start or resume playback of the loaded sequence."""
+        ...
+
+    def pause(self) -> None:
+        """Pause playback of the loaded sequence."""
+        ...
+
+    def seek(self, time: float) -> None:
+        """Jump to a time (in seconds) in the loaded sequence."""
+        ...
+
+    def set_speed(self, speed: float) -> None:
+        """Set the playback speed multiplier (1.0 is real time)."""
+        ...
+
+    def playback_time(self) -> float:
+        """Current time (in seconds) of the playback clock."""
+        ...
+
+    def is_playing(self) -> bool:
+        """Whether the loaded sequence is currently playing."""
+        ...
+
+    def set_world_up(self, up: Sequence[float]) -> None:
+        """Set the world up direction, [0, 0, 1] (Z-up) by default or e.g. [0, 1, 0] for Y-up.
+
+        The camera orbits around it and the grid is drawn on the plane perpendicular to it.
+
+        Raises:
+            ValueError: If up is zero or not finite
+        """
+        ...
+
+    def set_handedness(self, handedness: str) -> None:
+        """Set the handedness of frame coordinates: "right" (default) or "left".
+
+        With "left", the scene is mirrored along world Z so data from left-handed
+        engines (e.g. Unity) displays correctly.
+
+        Raises:
+            ValueError: If handedness is not "right" or "left"
+        """
+        ...
+
+    def set_fov(self, fov: float) -> None:
+        """Set the camera's vertical field of view in radians, clamped to about [1, 170] degrees."""
+        ...
+
+    def set_zoom_mode(self, mode: str) -> None:
+        """Choose whether zooming moves the camera ("dolly", default) or narrows its field of view ("fov").
+
+        Raises:
+            ValueError: If mode is not "dolly" or "fov"
+        """
+        ...
+
+    def set_standard_view(self, view: str) -> None:
+        """Look at the camera target from the "front", "side" or "top", keeping the distance to it.
+
+        Raises:
+            ValueError: If view is not "front", "side" or "top"
+        """
+        ...
+
+    def fit_view(self) -> bool:
+        """Point the camera at all frames, points, lines and meshes, from its current direction.
+
+        Returns False, leaving the camera unchanged, if the scene is empty.
+        """
+        ...
+
+    def set_2d_mode(self, enabled: bool) -> None:
+        """Look straight down at the ground plane orthographically, panning instead of orbiting when dragging.
+
+        While it is on, standard views and projection changes are ignored, and
+        resetting or placing the camera keeps it looking down. Turning it off
+        restores the previous view.
+        """
+        ...
+
+    def set_clip_planes(self, near: float, far: float) -> None:
+        """Set the near and far clipping distances in meters (0.1 and 100 by default).
+
+        Depth precision depends on far / near, and mostly on near: when overlapping
+        lines flicker (z-fighting), raise near as much as the closest geometry allows.
+
+        Raises:
+            ValueError: Unless 0 < near < far
+        """
+        ...
+
+    def set_zoom_limits(self, min_distance: float, max_distance: float) -> None:
+        """Limit how close to and far from its target (in meters) the camera can zoom.
+
+        Raises:
+            ValueError: Unless 0 < min_distance <= max_distance
+        """
+        ...
+
+    def set_pan_button(self, button: str) -> None:
+        """Choose the mouse button panning when dragged, "right" (default) or "middle". Middle always pans.
+
+        Raises:
+            ValueError: If button is not "right" or "middle"
+        """
+        ...
+
+    def set_keyboard_steps(self, orbit: float, zoom: float) -> None:
+        """Set the orbit in radians per arrow key press (default 0.05) and the zoom per + or - press,
+        in mouse wheel notches (default 0.25)."""
+        ...
+
+    def set_projection_mode(self, mode: str, height: float = 2.0) -> None:
+        """Switch between "perspective" and "orthographic" projection. Pressing O in the window toggles between the two.
+
+        In orthographic mode height is the visible vertical extent in meters, and zooming changes it.
+        """
+        ...
+
+    def set_camera(
+        self,
+        position: Sequence[float],
+        target: Sequence[float],
+        up: Optional[Sequence[float]] = None,
+    ) -> None:
+        """Place the camera at position (meters), looking at and orbiting around target.
+
+        up is the direction pointing up on screen, the current one by default.
+
+        Raises:
+            ValueError: If the pose is not finite or looks along the up direction
+        """
+        ...
+
+    def get_camera(self) -> Dict[str, List[float]]:
+        """Current camera pose, as "position", "target" and "up" [x, y, z] lists."""
+        ...
+
+    def focus_on_frame(self, name: str) -> bool:
+        """Orbit around a frame's origin, keeping the camera's distance and orientation.
+
+        Returns whether the frame exists.
+        """
+        ...
+
+    def reset_camera(self) -> None:
+        """Move the camera back to its initial viewpoint. Pressing R in the window does the same."""
+        ...
+
+    def view_matrix(self) -> npt.NDArray[np.float32]:
+        """Get the 4x4 view matrix the viewer is currently rendering with."""
+        ...
+
+    def projection_matrix(self) -> npt.NDArray[np.float32]:
+        """Get the 4x4 projection matrix the viewer is currently rendering with."""
+        ...
+
+    def set_color_by_name(self, enabled: bool) -> None:
+        """Color each frame's axes by a hash of its name (shades of one hue for X, Y, Z)."""
+        ...
+
+    def set_color_seed(self, seed: int) -> None:
+        """Set the seed of the name to color hash; another seed reshuffles the mapping."""
+        ...
+
+    def set_origin_axis_length(self, length: float) -> None:
+        """Set the length in meters of the world origin axes (default 0.1)."""
+        ...
+
+    def set_axis_length(self, length: float) -> None:
+        """Set the default length in meters of the frame axes (default 0.1).
+
+        Frames pushed with a scale keep their size relative to this default.
+
+        Raises:
+            ValueError: If length is not positive
+        """
+        ...
+
+    def set_line_widths(self, frame: float, grid: float) -> None:
+        """Set the width in pixels of the frame axes (default 3.0) and of the grid lines (default 1.0).
+
+        Frames styled with their own line width keep it. Drivers may clamp widths above 1 pixel.
+        """
+        ...
+
+    def set_background_color(self, r: float, g: float, b: float, a: float = 1.0) -> None:
+        """Set the color (components in [0, 1]) the window is cleared to, light gray by default."""
+        ...
+
+    def set_render_scale(self, scale: float) -> None:
+        """Render at `scale` times the window resolution and downsample (2.0 = 2x supersampling).
+
+        The scale is clamped to [0.25, 4.0].
+        """
+        ...
+
+    def set_origin_tethers(self, visible: bool) -> None:
+        """Draw a faint dashed line from every frame origin to the world origin."""
+        ...
+
+    def set_presentation_mode(self, enabled: bool) -> None:
+        """Hide the grids, origin axes, origin tethers, scale bar and axis indicator, e.g. for screenshots.
+
+        The individual settings are kept and apply again once this is turned off.
+        Pressing H in the window toggles it too.
+        """
+        ...
+
+    def set_origin_markers(self, visible: bool) -> None:
+        """Draw a dot at each frame's origin, colored per frame with style_frame."""
+        ...
+
+    def set_origin_label(self, label: Optional[str]) -> None:
+        """Draw `label` (e.g. "world") at the world origin, or nothing with None."""
+        ...
+
+    def set_grid_ticks(self, visible: bool) -> None:
+        """Label the world grid's axes with their distance from the origin every five grid lines (off by default)."""
+        ...
+
+    def set_show_labels(self, visible: bool) -> None:
+        """Show or hide the name of each frame next to its origin (shown by default)."""
+        ...
+
+    def set_scale_bar(self, visible: bool) -> None:
+        """Show or hide the 1 meter scale bar in the bottom-left corner.
+
+        Ticks mark every 10 centimeters.
+        """
+        ...
+
+    def set_gnomon(self, visible: bool) -> None:
+        """Show or hide the axis indicator in the bottom-right corner, which turns with the camera."""
+        ...
+
+    def list_monitors(self) -> List[str]:
+        """Names of the monitors connected when the window was opened (empty before start)."""
+        ...
+
+    def move_to_monitor(self, index: int) -> bool:
+        """Move the window to the center of the monitor at `index` in `list_monitors()`.
+
+        Returns:
+            False if there is no such monitor
+        """
+        ...
+
+    def reset_appearance(self) -> None:
+        """Restore the default background, world grid, axis colors, overlays and frame styles.
+
+        Frame transforms are left untouched.
+        """
+        ...
+
+    def render_stats(self) -> Dict[str, float]:
+        """Timing of the render loop, averaged over recent frames.
+
+        Returns:
+            "fps" (frames per second), "frame_time" (seconds spent drawing a frame)
+            and "dropped" (number of frames that took longer than 33 ms)
+        """
+        ...
+
+    def last_frame_time(self) -> Optional[float]:
+        """Seconds between the last two rendered frames, or None before the first frame."""
+        ...
+
+    def set_fps_overlay(self, visible: bool) -> None:
+        """Show or hide the frame rate and frame time in the top-left corner."""
+        ...
+
+    def set_max_cpu_usage(self, usage: Optional[float]) -> None:
+        """Throttle the frame rate so drawing takes at most `usage` (in (0, 1]) of the time.
+
+        None draws as fast as possible.
+        """
+        ...
+
+    def is_running(self) -> bool:
+        """Whether the viewer is started and its window is still open."""
         ...
 
     def stop(self) -> None:
         """Stop the viewer and close the window."""
-        ... 
\ No newline at end of file
+        ...
+
+    def __enter__(self) -> "Viewer":
+        """Start the viewer when entering a with block.
+
+        Raises:
+            RuntimeError: If the viewer fails to start
+        """
+        ...
+
+    def __exit__(
+        self,
+        exc_type: Optional[Type[BaseException]],
+        exc_value: Optional[BaseException],
+        traceback: Optional[TracebackType],
+    ) -> bool:
+        """Stop the viewer and close the window when leaving a with block."""
+        ...
@@ -0,0 +1,137 @@
+use winit::event::VirtualKeyCode;
+
+use crate::camera::ProjectionMode;
+use crate::config::{GridMode, RenderState, ViewerConfig};
+use crate::Viewer;
+
+/// Builder for a [`Viewer`] with non-default options.
+///
+/// ```no_run
+/// use frames_viewer::ViewerBuilder;
+///
+/// let viewer = ViewerBuilder::new().vsync(true).build();
+/// viewer.start().unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ViewerBuilder {
+    config: ViewerConfig,
+}
+
+impl ViewerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start from an existing configuration.
+    pub fn from_config(config: ViewerConfig) -> Self {
+        ViewerBuilder { config }
+    }
+
+    /// Window title ("Frames Viewer" by default).
+    pub fn title(mut self, title: &str) -> Self {
+        self.config.title = title.to_string();
+        self
+    }
+
+    /// Initial window size in logical pixels (800x600 by default).
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.config.width = width;
+        self.config.height = height;
+        self
+    }
+
+    /// Render offscreen instead of opening a window (see [`Viewer::new_headless`]).
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.config.headless = headless;
+        self
+    }
+
+    /// Synchronize buffer swaps with the display refresh rate (off by default).
+    ///
+    /// With vsync on, fast camera motion no longer tears and the render loop is
+    /// throttled to the refresh rate, which also lowers CPU usage. With it off,
+    /// the loop redraws as fast as it can, giving the lowest latency at the cost
+    /// of a fully busy render thread; [`Viewer::set_max_cpu_usage`](crate::Viewer::set_max_cpu_usage)
+    /// can cap it instead. The setting is fixed once the window is created.
+    pub fn vsync(mut self, vsync: bool) -> Self {
+        self.config.vsync = vsync;
+        self
+    }
+
+    /// Samples per pixel used to anti-alias lines (4 by default), a power of two,
+    /// or 0 to disable multisampling, e.g. on drivers without multisampled pixel formats.
+    /// Other counts are rounded down to a power of two.
+    pub fn msaa_samples(mut self, samples: u16) -> Self {
+        self.config.msaa_samples = samples;
+        self
+    }
+
+    /// Keep the window above all other windows (off by default).
+    pub fn always_on_top(mut self, always_on_top: bool) -> Self {
+        self.config.always_on_top = always_on_top;
+        self
+    }
+
+    /// Show the window title bar and borders (on by default).
+    ///
+    /// Together with [`always_on_top`](Self::always_on_top), turning this off
+    /// gives a floating, borderless window suited to dashboards.
+    pub fn decorations(mut self, decorations: bool) -> Self {
+        self.config.decorations = decorations;
+        self
+    }
+
+    /// Key closing the window (Escape by default), or `None` to only close it
+    /// from the title bar or [`Viewer::stop`], e.g. for kiosk displays.
+    pub fn close_key(mut self, key: Option<VirtualKeyCode>) -> Self {
+        self.config.close_key = key;
+        self
+    }
+
+    /// Draw the frame rate and frame time in the top-left corner (off by default).
+    pub fn fps_overlay(mut self, visible: bool) -> Self {
+        self.config.fps_overlay = visible;
+        self
+    }
+
+    /// Initial camera position and the point it looks at, in meters.
+    ///
+    /// Invalid viewpoints (non-finite, or looking along the world up axis)
+    /// fall back to the default.
+    pub fn camera(mut self, position: [f32; 3], target: [f32; 3]) -> Self {
+        self.config.camera.position = position;
+        self.config.camera.target = target;
+        self
+    }
+
+    /// Initial camera projection, perspective by default.
+    ///
+    /// An orthographic height that is not finite and positive falls back to the
+    /// default camera.
+    pub fn projection(mut self, projection: ProjectionMode) -> Self {
+        self.config.camera.projection = projection;
+        self
+    }
+
+    /// World up direction, Z-up (REP-103) by default. See [`Viewer::set_world_up`].
+    pub fn world_up(mut self, up: [f32; 3]) -> Self {
+        self.config.world_up = up;
+        self
+    }
+
+    /// Draw the grid on the ground plane only (the default) or in all three planes.
+    pub fn grid_mode(mut self, mode: GridMode) -> Self {
+        self.config.grid.mode = mode;
+        self
+    }
+
+    /// OpenGL capabilities and functions the renderer sets up (see [`RenderState`]).
+    pub fn render_state(mut self, state: RenderState) -> Self {
+        self.config.render_state = state;
+        self
+    }
+
+    pub fn build(self) -> Viewer {
+        Viewer::from_config(self.config)
+    }
+}
@@ -1,4 +1,67 @@
 use nalgebra as na;
+use serde::{Deserialize, Serialize};
+
+/// Handedness of the coordinate system frames are expressed in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Handedness {
+    /// X, Y, Z follow the right-hand rule (OpenGL, ROS, numpy conventions)
+    #[default]
+    RightHanded,
+    /// X, Y, Z follow the left-hand rule (Unity, Direct3D conventions)
+    LeftHanded,
+}
+
+/// How the camera projects the scene onto the screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ProjectionMode {
+    /// Objects shrink with distance, using the camera's field of view
+    #[default]
+    Perspective,
+    /// Parallel lines stay parallel; `height` is the visible vertical extent in meters
+    Orthographic { height: f32 },
+}
+
+/// What zooming (mouse wheel, `+` and `-` keys) changes in perspective projection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ZoomMode {
+    /// Move the camera towards or away from its target
+    #[default]
+    Dolly,
+    /// Narrow or widen the field of view, keeping the camera in place so it
+    /// never gets close enough to clip into geometry
+    Fov,
+}
+
+/// Canonical viewpoints, as on the numeric keypad of Blender.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StandardView {
+    /// Horizontal, along +Y when Z is up (or -Z when Y is up)
+    Front,
+    /// Horizontal, along -X
+    Side,
+    /// Straight down the world up direction
+    Top,
+}
+
+impl StandardView {
+    /// Direction the camera looks along for this view.
+    fn direction(self, world_up: na::Vector3<f32>) -> na::Vector3<f32> {
+        let up = world_up.normalize();
+        let side = if up.x.abs() < 0.9 { -na::Vector3::x() } else { -na::Vector3::y() };
+        let side = (side - up * up.dot(&side)).normalize();
+        let front = side.cross(&up);
+        match self {
+            StandardView::Front => front,
+            StandardView::Side => side,
+            // Looking exactly along up would leave the roll undefined, so tilt
+            // by a hair to keep the front direction pointing up on screen
+            StandardView::Top => (-up + front * 1e-3).normalize(),
+        }
+    }
+}
+
+const MIN_FOV: f32 = 0.017; // About 1 degree
+const MAX_FOV: f32 = 2.97; // About 170 degrees
 
 pub struct Camera {
     position: na::Point3<f32>,
@@ -8,33 +71,217 @@ pub struct Camera {
     aspect: f32,
     near: f32,
     far: f32,
+    handedness: Handedness,
+    projection: ProjectionMode,
+    zoom_mode: ZoomMode,
+    min_distance: f32, // Zoom limits, as distances to the target
+    max_distance: f32,
+    home: (na::Point3<f32>, na::Point3<f32>, na::Vector3<f32>), // Position, target and up restored by `reset`
+    before_top_down: Option<(na::Vector3<f32>, na::Vector3<f32>, ProjectionMode)>, // Offset from the target, up and projection
 }
 
 impl Camera {
     pub fn new(aspect: f32) -> Self {
+        let position = na::Point3::new(1.5, 1.0, 2.0);
+        let target = na::Point3::new(0.0, 0.0, 0.0);
+        let up = na::Vector3::new(0.0, 0.0, 1.0);
         Camera {
-            position: na::Point3::new(1.5, 1.0, 2.0),
-            target: na::Point3::new(0.0, 0.0, 0.0),
-            up: na::Vector3::new(0.0, 0.0, 1.0),
+            position,
+            target,
+            up,
             fov: std::f32::consts::PI / 4.0,
             aspect,
             near: 0.1,
             far: 100.0,
+            handedness: Handedness::RightHanded,
+            projection: ProjectionMode::Perspective,
+            zoom_mode: ZoomMode::Dolly,
+            min_distance: 0.05,
+            max_distance: 50.0,
+            home: (position, target, up),
+            before_top_down: None,
         }
     }
 
+    /// Make the current viewpoint the one [`reset`](Self::reset) returns to.
+    pub fn set_home(&mut self) {
+        self.home = (self.position, self.target, self.up);
+    }
+
+    /// Return to the home viewpoint, keeping the aspect ratio and field of view.
+    pub fn reset(&mut self) {
+        (self.position, self.target, self.up) = self.home;
+    }
+
     pub fn view_matrix(&self) -> na::Matrix4<f32> {
-        na::Matrix4::look_at_rh(&self.position, &self.target, &self.up)
+        let view = na::Matrix4::look_at_rh(&self.position, &self.target, &self.up);
+        match self.handedness {
+            Handedness::RightHanded => view,
+            // Mirror the world along Z so left-handed data displays unflipped
+            Handedness::LeftHanded => view * na::Matrix4::from_diagonal(&na::Vector4::new(1.0, 1.0, -1.0, 1.0)),
+        }
+    }
+
+    pub fn set_handedness(&mut self, handedness: Handedness) {
+        self.handedness = handedness;
     }
 
     pub fn projection_matrix(&self) -> na::Matrix4<f32> {
-        na::Matrix4::new_perspective(self.aspect, self.fov, self.near, self.far)
+        match self.projection {
+            ProjectionMode::Perspective => na::Matrix4::new_perspective(self.aspect, self.fov, self.near, self.far),
+            ProjectionMode::Orthographic { height } => {
+                let (half_width, half_height) = (height * self.aspect / 2.0, height / 2.0);
+                na::Matrix4::new_orthographic(-half_width, half_width, -half_height, half_height, self.near, self.far)
+            }
+        }
+    }
+
+    pub fn projection_mode(&self) -> ProjectionMode {
+        self.projection
+    }
+
+    pub fn set_projection_mode(&mut self, mode: ProjectionMode) {
+        self.projection = mode;
+    }
+
+    /// Switch between perspective and an orthographic projection showing
+    /// about the same extent at the target.
+    pub fn toggle_projection(&mut self) {
+        self.projection = match self.projection {
+            ProjectionMode::Perspective => {
+                let distance = (self.position - self.target).norm();
+                ProjectionMode::Orthographic { height: 2.0 * distance * (self.fov / 2.0).tan() }
+            }
+            ProjectionMode::Orthographic { .. } => ProjectionMode::Perspective,
+        };
+    }
+
+    /// Look straight down `world_up` at the target through an orthographic
+    /// projection showing about the same extent, remembering the current view
+    /// for [`leave_top_down`](Self::leave_top_down).
+    pub fn enter_top_down(&mut self, world_up: na::Vector3<f32>) {
+        let world_up = world_up.normalize();
+        if self.before_top_down.is_none() {
+            self.before_top_down = Some((self.position - self.target, self.up, self.projection));
+        }
+        let distance = (self.position - self.target).norm();
+
+        // Keep the world Y axis pointing up on screen, unless it is the world up itself
+        let y = na::Vector3::y();
+        let screen_up = y - world_up * world_up.dot(&y);
+        let screen_up = if screen_up.norm() > 1e-3 { screen_up.normalize() } else { -na::Vector3::z() };
+
+        self.position = self.target + world_up * distance;
+        self.up = screen_up;
+        self.projection = ProjectionMode::Orthographic { height: 2.0 * distance * (self.fov / 2.0).tan() };
+    }
+
+    /// Return to the view and projection used before [`enter_top_down`](Self::enter_top_down),
+    /// keeping the current target.
+    pub fn leave_top_down(&mut self) {
+        if let Some((offset, up, projection)) = self.before_top_down.take() {
+            self.position = self.target + offset;
+            self.up = up;
+            self.projection = projection;
+        }
+    }
+
+    /// Pixel coordinates (origin at the top-left) of `point` in a `width` x `height` viewport.
+    ///
+    /// Returns `None` if the point is behind the camera or outside the viewport.
+    pub fn project(&self, point: &na::Point3<f32>, width: u32, height: u32) -> Option<(f32, f32)> {
+        let clip = self.projection_matrix() * self.view_matrix() * point.to_homogeneous();
+        if clip.w <= 0.0 {
+            return None;
+        }
+        let (x, y) = (clip.x / clip.w, clip.y / clip.w);
+        if x.abs() > 1.0 || y.abs() > 1.0 {
+            return None;
+        }
+        Some(((x + 1.0) / 2.0 * width as f32, (1.0 - y) / 2.0 * height as f32))
+    }
+
+    pub fn position(&self) -> na::Point3<f32> {
+        self.position
+    }
+
+    pub fn target(&self) -> na::Point3<f32> {
+        self.target
+    }
+
+    pub fn up(&self) -> na::Vector3<f32> {
+        self.up
+    }
+
+    /// Unit vector pointing to the right of the view direction.
+    pub fn right(&self) -> na::Vector3<f32> {
+        (self.target - self.position).cross(&self.up).normalize()
     }
 
     pub fn set_aspect(&mut self, aspect: f32) {
         self.aspect = aspect;
     }
 
+    /// Place the camera at `position`, looking at (and orbiting around) `target`.
+    pub fn look_at(&mut self, position: na::Point3<f32>, target: na::Point3<f32>) {
+        self.position = position;
+        self.target = target;
+    }
+
+    /// Look at the target along `direction`, keeping the distance to it.
+    pub fn view_direction(&mut self, direction: na::Vector3<f32>) {
+        let distance = (self.position - self.target).norm();
+        self.position = self.target - direction.normalize() * distance;
+    }
+
+    /// Look at the target from one of the canonical viewpoints, keeping the distance to it.
+    pub fn set_standard_view(&mut self, view: StandardView) {
+        self.view_direction(view.direction(self.up));
+    }
+
+    /// Look at `center` from the current direction, just close enough for a
+    /// sphere of `radius` around it to fill the view, within the zoom limits.
+    pub fn fit(&mut self, center: na::Point3<f32>, radius: f32) {
+        // The narrower of the vertical and horizontal fields of view decides
+        let half_fov = (self.fov / 2.0).tan().min((self.fov / 2.0).tan() * self.aspect).atan();
+        let distance = (radius / half_fov.sin()).clamp(self.min_distance, self.max_distance);
+        let direction = (self.position - self.target).normalize();
+        self.target = center;
+        self.position = center + direction * distance;
+        if let ProjectionMode::Orthographic { height } = &mut self.projection {
+            *height = (2.0 * radius * 1.0f32.max(1.0 / self.aspect)).clamp(self.min_distance, self.max_distance);
+        }
+    }
+
+    /// Move the target to `target`, keeping the camera's distance and orientation to it.
+    pub fn focus(&mut self, target: na::Point3<f32>) {
+        self.position = target + (self.position - self.target);
+        self.target = target;
+    }
+
+    /// Set the vertical field of view in radians, clamped to about [1, 170] degrees.
+    pub fn set_fov(&mut self, fov: f32) {
+        self.fov = fov.clamp(MIN_FOV, MAX_FOV);
+    }
+
+    pub fn fov(&self) -> f32 {
+        self.fov
+    }
+
+    pub fn set_zoom_mode(&mut self, mode: ZoomMode) {
+        self.zoom_mode = mode;
+    }
+
+    pub fn set_up(&mut self, up: na::Vector3<f32>) {
+        self.up = up.normalize();
+    }
+
+    /// Change the up direction of both the current and the home viewpoints.
+    pub fn set_world_up(&mut self, up: na::Vector3<f32>) {
+        self.set_up(up);
+        self.home.2 = self.up;
+    }
+
     pub fn orbit(&mut self, delta_x: f32, delta_y: f32) {
         let right = (self.position - self.target).cross(&self.up).normalize();
         
@@ -62,9 +309,49 @@ impl Camera {
         self.target += movement * scale;
     }
 
+    /// Move towards (positive `delta`) or away from the target, staying within the zoom limits.
+    ///
+    /// In orthographic mode the visible height shrinks or grows instead, within the same limits.
+    /// With [`ZoomMode::Fov`], perspective zooming narrows or widens the field of view.
     pub fn zoom(&mut self, delta: f32) {
-        let view_dir = (self.target - self.position).normalize();
-        let movement = view_dir * delta * 0.2;
-        self.position += movement;
+        if let ProjectionMode::Orthographic { height } = &mut self.projection {
+            *height = (*height * (-delta * 0.1).exp()).clamp(self.min_distance, self.max_distance);
+            return;
+        }
+        if self.zoom_mode == ZoomMode::Fov {
+            self.set_fov(self.fov * (-delta * 0.1).exp());
+            return;
+        }
+        let offset = self.position - self.target;
+        let distance = (offset.norm() - delta * 0.2).clamp(self.min_distance, self.max_distance);
+        self.position = self.target + offset.normalize() * distance;
+    }
+
+    /// Distances from the camera of the near and far clipping planes.
+    ///
+    /// Returns false, leaving the planes unchanged, unless `0 < near < far`.
+    pub fn set_clip_planes(&mut self, near: f32, far: f32) -> bool {
+        if !(near > 0.0 && far > near && far.is_finite()) {
+            return false;
+        }
+        self.near = near;
+        self.far = far;
+        true
+    }
+
+    pub fn clip_planes(&self) -> (f32, f32) {
+        (self.near, self.far)
+    }
+
+    /// Closest and farthest distances to the target that zooming can reach.
+    ///
+    /// Returns false, leaving the limits unchanged, unless `0 < min <= max`.
+    pub fn set_zoom_limits(&mut self, min_distance: f32, max_distance: f32) -> bool {
+        if !(min_distance > 0.0 && max_distance >= min_distance && max_distance.is_finite()) {
+            return false;
+        }
+        self.min_distance = min_distance;
+        self.max_distance = max_distance;
+        true
     }
 } 
\ No newline at end of file
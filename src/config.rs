@@ -0,0 +1,317 @@
+use nalgebra as na;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use winit::event::{MouseButton, VirtualKeyCode};
+
+use crate::camera::{Handedness, ProjectionMode, ZoomMode};
+use crate::renderer::AXIS_LENGTH;
+
+const MAX_GRID_LINES: f32 = 10_000.0; // Along each axis, bounding the grid's vertex buffer
+
+/// Runtime options of the viewer, serializable so a setup can be saved and shared.
+///
+/// Missing fields fall back to their defaults when deserializing.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewerConfig {
+    /// Window title
+    pub title: String,
+    /// Initial window width in logical pixels
+    pub width: u32,
+    /// Initial window height in logical pixels
+    pub height: u32,
+    /// Keep the window above all other windows
+    pub always_on_top: bool,
+    /// Show the window title bar and borders
+    pub decorations: bool,
+    /// Key closing the window, if any. Not serialized
+    #[serde(skip)]
+    pub close_key: Option<VirtualKeyCode>,
+    /// Render offscreen without opening a window. Not serialized
+    #[serde(skip)]
+    pub headless: bool,
+    /// Synchronize buffer swaps with the display refresh rate. Only read when
+    /// the window is created, since changing it means recreating the GL context
+    pub vsync: bool,
+    /// Samples per pixel of the window's multisampled framebuffer, smoothing
+    /// jagged lines. 0 disables multisampling. Only read when the window is created
+    pub msaa_samples: u16,
+    /// Maximum share of the render thread's time spent drawing, throttling the frame rate
+    pub max_cpu_usage: Option<f32>,
+    /// Resolution multiplier of the rendered image (2.0 supersamples 2x)
+    pub render_scale: f32,
+    /// Background (clear) color as RGBA
+    pub background: [f32; 4],
+    pub grid: GridConfig,
+    /// Width of the frame axes in pixels, for frames without a width of their own
+    pub frame_line_width: f32,
+    /// Width of the grid lines in pixels
+    pub grid_line_width: f32,
+    pub camera: CameraConfig,
+    pub render_state: RenderState,
+    /// Color each frame's axes by a hash of its name instead of red/green/blue
+    pub color_by_name: bool,
+    /// Seed of the name hash, changing it reshuffles the name to color mapping
+    pub color_seed: u64,
+    /// Length of the frame axes in meters, before per-frame scaling
+    pub axis_length: f32,
+    /// Length of the world origin axes in meters
+    pub origin_axis_length: f32,
+    /// Draw a dashed line from every frame origin to the world origin
+    pub origin_tethers: bool,
+    /// Hide the grids, origin axes, origin tethers, scale bar and axis indicator regardless of their own settings
+    pub presentation_mode: bool,
+    /// Look straight down at the ground plane orthographically; dragging pans instead of orbiting
+    pub mode_2d: bool,
+    /// Draw each frame's name next to its origin
+    pub show_labels: bool,
+    /// Draw a dot at each frame's origin
+    pub origin_markers: bool,
+    /// Draw the frame rate and frame time in the top-left corner
+    pub fps_overlay: bool,
+    /// Draw a 1 meter scale bar in the bottom-left corner
+    pub scale_bar: bool,
+    /// Draw a small axis indicator in the bottom-right corner showing the camera orientation
+    pub gnomon: bool,
+    /// Text drawn at the world origin, if any
+    pub origin_label: Option<String>,
+    /// Label the world grid axes with their distance from the origin
+    pub grid_ticks: bool,
+    /// Half-life after which a frame that stopped updating is drawn at half opacity
+    pub age_fade: Option<Duration>,
+    /// Hide frames that have not been pushed for longer than this
+    pub stale_hide: Option<Duration>,
+    /// Time over which a just-pushed frame's enlarged axes shrink back to normal
+    pub update_pulse: Option<Duration>,
+    /// Handedness of the coordinate system frames are expressed in
+    pub handedness: Handedness,
+    /// What the mouse wheel and the `+` and `-` keys change when zooming in perspective
+    pub zoom_mode: ZoomMode,
+    /// World up direction used by the camera
+    pub world_up: [f32; 3],
+    /// Radians of orbit per pixel of mouse motion
+    pub orbit_sensitivity: f32,
+    /// Pan amount per pixel of mouse motion
+    pub pan_sensitivity: f32,
+    /// Mouse button panning when dragged, in addition to the middle one. Not serialized
+    #[serde(skip, default = "default_pan_button")]
+    pub pan_button: MouseButton,
+    /// Multiplier applied to mouse wheel zoom
+    pub zoom_sensitivity: f32,
+    /// Radians of orbit per press of an arrow key
+    pub key_orbit_step: f32,
+    /// Zoom amount per press of `+` or `-`, in mouse wheel notches
+    pub key_zoom_step: f32,
+}
+
+/// Grid drawn from the world origin.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridConfig {
+    /// Extent of the grid in meters
+    pub size: f32,
+    /// Spacing between grid lines in meters
+    pub step: f32,
+    pub visible: bool,
+    pub mode: GridMode,
+    pub style: GridStyle,
+}
+
+/// How grid lines are drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GridStyle {
+    #[default]
+    Solid,
+    /// Dashes a quarter of the grid step long, less cluttering than solid lines
+    Dashed,
+}
+
+/// Planes the grid is drawn in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GridMode {
+    /// Only the ground plane, perpendicular to the world up axis
+    #[default]
+    Ground,
+    /// The XY, XZ and YZ planes
+    ThreePlanes,
+    /// Any combination of the XY, XZ and YZ planes
+    Planes(GridPlanes),
+}
+
+/// Set of world planes for [`GridMode::Planes`], combined with `|`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GridPlanes {
+    pub xy: bool,
+    pub xz: bool,
+    pub yz: bool,
+}
+
+impl GridPlanes {
+    pub const NONE: Self = Self { xy: false, xz: false, yz: false };
+    pub const XY: Self = Self { xy: true, ..Self::NONE };
+    pub const XZ: Self = Self { xz: true, ..Self::NONE };
+    pub const YZ: Self = Self { yz: true, ..Self::NONE };
+    pub const ALL: Self = Self { xy: true, xz: true, yz: true };
+}
+
+impl std::ops::BitOr for GridPlanes {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self { xy: self.xy || other.xy, xz: self.xz || other.xz, yz: self.yz || other.yz }
+    }
+}
+
+fn default_pan_button() -> MouseButton {
+    MouseButton::Right
+}
+
+/// OpenGL state set up by the renderer when it is created.
+///
+/// Lets code issuing its own GL calls in the same context know, and choose,
+/// which global state the viewer relies on. Functions are OpenGL enum values.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderState {
+    pub depth_test: bool,
+    pub blend: bool,
+    pub line_smooth: bool,
+    /// Depth comparison, e.g. `gl::LEQUAL`
+    pub depth_func: u32,
+    /// Source and destination blend factors, e.g. `[gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA]`
+    pub blend_func: [u32; 2],
+}
+
+/// Initial viewpoint of the camera.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraConfig {
+    pub position: [f32; 3],
+    /// Point the camera looks at and orbits around
+    pub target: [f32; 3],
+    /// Vertical field of view in radians
+    pub fov: f32,
+    /// Perspective by default; an orthographic height must be finite and positive
+    pub projection: ProjectionMode,
+}
+
+impl GridConfig {
+    /// Whether the size and step are finite and positive, with at most 10000
+    /// lines along each axis.
+    pub fn is_valid(&self) -> bool {
+        let positive = |v: f32| v.is_finite() && v > 0.0;
+        positive(self.size) && positive(self.step) && self.size / self.step <= MAX_GRID_LINES
+    }
+
+    /// Planes the grid is drawn in, by index of their normal axis.
+    pub(crate) fn planes(&self, world_up: [f32; 3]) -> [bool; 3] {
+        match self.mode {
+            GridMode::ThreePlanes => [true; 3],
+            GridMode::Planes(planes) => [planes.yz, planes.xz, planes.xy],
+            GridMode::Ground => {
+                let up = world_up.map(f32::abs);
+                let normal = if up[0] >= up[1] && up[0] >= up[2] { 0 } else if up[1] >= up[2] { 1 } else { 2 };
+                [normal == 0, normal == 1, normal == 2]
+            }
+        }
+    }
+}
+
+impl CameraConfig {
+    /// Whether this viewpoint can be used with the given world up direction.
+    pub fn is_valid(&self, world_up: [f32; 3]) -> bool {
+        let finite = self.position.iter().chain(&self.target).all(|v| v.is_finite());
+        let direction = na::Vector3::from(self.target) - na::Vector3::from(self.position);
+        let aligned_with_up = direction.cross(&na::Vector3::from(world_up)).norm() < 1e-6;
+        let projection = match self.projection {
+            ProjectionMode::Perspective => true,
+            ProjectionMode::Orthographic { height } => height.is_finite() && height > 0.0,
+        };
+        finite && !aligned_with_up && projection && self.fov > 0.0 && self.fov < std::f32::consts::PI
+    }
+}
+
+impl Default for ViewerConfig {
+    fn default() -> Self {
+        ViewerConfig {
+            title: "Frames Viewer".to_string(),
+            width: 800,
+            height: 600,
+            always_on_top: false,
+            decorations: true,
+            close_key: Some(VirtualKeyCode::Escape),
+            headless: false,
+            vsync: false,
+            msaa_samples: 4,
+            max_cpu_usage: None,
+            render_scale: 1.0,
+            background: [0.95, 0.95, 0.95, 1.0], // Light gray background
+            grid: GridConfig::default(),
+            frame_line_width: 3.0,
+            grid_line_width: 1.0,
+            render_state: RenderState::default(),
+            camera: CameraConfig::default(),
+            color_by_name: false,
+            color_seed: 0,
+            axis_length: AXIS_LENGTH,
+            origin_axis_length: 0.1,
+            origin_tethers: false,
+            presentation_mode: false,
+            mode_2d: false,
+            show_labels: true,
+            origin_markers: false,
+            fps_overlay: false,
+            scale_bar: false,
+            gnomon: false,
+            origin_label: None,
+            grid_ticks: false,
+            age_fade: None,
+            stale_hide: None,
+            update_pulse: None,
+            handedness: Handedness::RightHanded,
+            zoom_mode: ZoomMode::Dolly,
+            world_up: [0.0, 0.0, 1.0],
+            orbit_sensitivity: 0.01,
+            pan_sensitivity: 0.08,
+            pan_button: default_pan_button(),
+            zoom_sensitivity: 1.0,
+            key_orbit_step: 0.05,
+            key_zoom_step: 0.25,
+        }
+    }
+}
+
+impl Default for CameraConfig {
+    fn default() -> Self {
+        CameraConfig {
+            position: [1.5, 1.0, 2.0],
+            target: [0.0, 0.0, 0.0],
+            fov: std::f32::consts::PI / 4.0,
+            projection: ProjectionMode::Perspective,
+        }
+    }
+}
+
+impl Default for RenderState {
+    fn default() -> Self {
+        RenderState {
+            depth_test: true,
+            blend: true,
+            line_smooth: true,
+            depth_func: gl::LEQUAL,
+            blend_func: [gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA],
+        }
+    }
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        GridConfig {
+            size: 1.0, // 1 meter
+            step: 0.1, // 10 centimeters
+            visible: true,
+            mode: GridMode::Ground,
+            style: GridStyle::Solid,
+        }
+    }
+}
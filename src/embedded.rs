@@ -0,0 +1,52 @@
+use std::time::Instant;
+
+use crate::renderer::Renderer;
+use crate::Viewer;
+
+/// Draws a [`Viewer`]'s scene into an OpenGL context owned by a host
+/// application (e.g. a Qt widget), instead of a window of its own.
+///
+/// Created with [`Viewer::embed`]. The host calls [`render`](Self::render)
+/// whenever it repaints, with its context current, and drops it before
+/// destroying that context.
+pub struct EmbeddedRenderer {
+    viewer: Viewer,
+    renderer: Renderer,
+    last_tick: Instant,
+}
+
+impl EmbeddedRenderer {
+    pub(crate) fn new(viewer: Viewer, renderer: Renderer) -> Self {
+        EmbeddedRenderer {
+            viewer,
+            renderer,
+            last_tick: Instant::now(),
+        }
+    }
+
+    /// Set the size of the drawing area in physical pixels.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.renderer.resize(width, height);
+        self.viewer.camera.write().set_aspect(width as f32 / height as f32);
+        *self.viewer.window_size.write() = (width, height);
+    }
+
+    /// Apply pending updates and draw the scene into the currently bound framebuffer.
+    pub fn render(&mut self) {
+        let now = Instant::now();
+        let dt = now.duration_since(self.last_tick).as_secs_f64();
+        self.last_tick = now;
+
+        let requests = self.viewer.update(dt, None);
+        self.viewer.draw(&mut self.renderer, now);
+        self.viewer.answer(&mut self.renderer, requests);
+        self.viewer.record(&self.renderer, dt);
+    }
+}
+
+impl Drop for EmbeddedRenderer {
+    fn drop(&mut self) {
+        *self.viewer.running.write() = false;
+        self.viewer.reject_draw_requests();
+    }
+}
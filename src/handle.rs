@@ -0,0 +1,41 @@
+use std::sync::mpsc::Sender;
+
+use crate::{check_transform, Command, Result, Transform};
+
+/// Cheap, cloneable handle sending frame updates to a [`Viewer`](crate::Viewer).
+///
+/// Updates are queued on a channel and applied by the render loop, so tasks
+/// holding a handle (e.g. async tasks) never block on the viewer's locks.
+/// They only take effect once the viewer is started.
+#[derive(Clone)]
+pub struct ViewerHandle {
+    sender: Sender<Command>,
+}
+
+impl ViewerHandle {
+    pub(crate) fn new(sender: Sender<Command>) -> Self {
+        ViewerHandle { sender }
+    }
+
+    /// Add a frame or update an existing one.
+    ///
+    /// Fails without sending anything if `transform` contains NaN or infinite values.
+    pub fn push_frame(&self, transform: Transform, name: &str) -> Result<()> {
+        check_transform(&transform, name)?;
+        self.send(Command::PushFrame(name.to_string(), transform));
+        Ok(())
+    }
+
+    pub fn remove_frame(&self, name: &str) {
+        self.send(Command::RemoveFrame(name.to_string()));
+    }
+
+    pub fn clear_frames(&self) {
+        self.send(Command::ClearFrames);
+    }
+
+    fn send(&self, command: Command) {
+        // The receiver lives as long as the viewer; updates sent after it is dropped are moot
+        let _ = self.sender.send(command);
+    }
+}
@@ -0,0 +1,57 @@
+use glutin_egl::api::egl::context::PossiblyCurrentContext;
+use glutin_egl::api::egl::device::Device;
+use glutin_egl::api::egl::display::Display;
+use glutin_egl::config::{ConfigSurfaceTypes, ConfigTemplateBuilder};
+use glutin_egl::context::{ContextApi, ContextAttributesBuilder, Version};
+use glutin_egl::display::GlDisplay;
+use std::ffi::{c_void, CString};
+
+use crate::{Result, ViewerError};
+
+/// OpenGL 3.3 context, current on the thread that created it, without any
+/// window or surface to draw to.
+///
+/// Created on an EGL device (a GPU, or Mesa's software renderer), so unlike a
+/// winit event loop it needs no X11 or Wayland display.
+pub(crate) struct HeadlessContext {
+    display: Display,
+    _context: PossiblyCurrentContext,
+}
+
+impl HeadlessContext {
+    /// Create a context on the first EGL device that provides one and make it current.
+    pub fn new() -> Result<Self> {
+        let devices = Device::query_devices().map_err(|e| ViewerError::ContextCreationError(e.to_string()))?;
+        let mut error = "No EGL device found".to_string();
+        for device in devices {
+            match unsafe { Self::on_device(&device) } {
+                Ok(context) => return Ok(context),
+                Err(e) => error = e.to_string(),
+            }
+        }
+        Err(ViewerError::ContextCreationError(error))
+    }
+
+    unsafe fn on_device(device: &Device) -> glutin_egl::error::Result<Self> {
+        let display = Display::with_device(device, None)?;
+        let template = ConfigTemplateBuilder::new().with_surface_type(ConfigSurfaceTypes::empty()).build();
+        let config = display
+            .find_configs(template)?
+            .next()
+            .ok_or(glutin_egl::error::ErrorKind::BadConfig)?;
+        let attributes = ContextAttributesBuilder::new()
+            .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
+            .build(None);
+        let context = display.create_context(&config, &attributes)?.make_current_surfaceless()?;
+        Ok(HeadlessContext {
+            display,
+            _context: context,
+        })
+    }
+
+    /// Address of an OpenGL function, for [`gl::load_with`].
+    pub fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        let symbol = CString::new(symbol).unwrap();
+        self.display.get_proc_address(&symbol)
+    }
+}
@@ -1,178 +1,3128 @@
 mod renderer;
+mod builder;
 mod camera;
+mod color;
+mod embedded;
+mod handle;
+mod headless;
+mod config;
+mod font;
+mod playback;
 mod python;
+mod recording;
+mod screenshot;
+mod stats;
+mod tum;
+#[cfg(all(test, feature = "headless"))]
+mod smoke_test;
 
 use glutin::{
     Api, ContextBuilder, GlRequest,
 };
 use nalgebra as na;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
 use std::sync::Arc;
-use std::thread;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
-use winit::event::{Event, WindowEvent, MouseButton, ElementState, DeviceEvent, MouseScrollDelta};
-use winit::event_loop::{ControlFlow, EventLoopBuilder};
+use winit::event::{Event, ElementState, DeviceEvent, MouseScrollDelta, KeyboardInput};
+use winit::event_loop::{ControlFlow, EventLoop, EventLoopBuilder};
+use winit::platform::run_return::EventLoopExtRunReturn;
 use winit::platform::unix::EventLoopBuilderExtUnix;
 use winit::window::WindowBuilder;
 
-use crate::renderer::Renderer;
+use crate::camera::Camera;
+use crate::headless::HeadlessContext;
+use crate::playback::Playback;
+use crate::recording::Recording;
+use crate::screenshot::Screenshot;
+use crate::renderer::{AxesStyle, PickTarget, Primitive, Renderer, AXIS_LENGTH, FLOATS_PER_VERTEX};
+
+pub use crate::builder::ViewerBuilder;
+pub use crate::camera::{Handedness, ProjectionMode, StandardView, ZoomMode};
+pub use crate::embedded::EmbeddedRenderer;
+pub use crate::handle::ViewerHandle;
+pub use crate::config::{CameraConfig, GridConfig, GridMode, GridPlanes, GridStyle, RenderState, ViewerConfig};
+pub use crate::playback::Sample;
+pub use crate::stats::RenderStats;
+pub use winit::event::{MouseButton, VirtualKeyCode, WindowEvent};
 
 #[derive(Error, Debug)]
 pub enum ViewerError {
     #[error("Window creation failed")]
     WindowCreationError(#[from] winit::error::OsError),
-    #[error("OpenGL context creation failed")]
+    #[error("OpenGL context creation failed: {0}")]
     ContextCreationError(String),
+    #[error("Shader program creation failed: {0}")]
+    ShaderError(String),
+    #[error("I/O error")]
+    IoError(#[from] std::io::Error),
+    #[error("PNG encoding failed")]
+    PngError(#[from] png::EncodingError),
+    #[error("Viewer is not running")]
+    NotRunning,
+    #[error("Transform of frame '{0}' contains NaN or infinite values")]
+    InvalidTransform(String),
+    #[error("Invalid mesh: {0}")]
+    InvalidMesh(String),
+    #[error("OBJ loading failed")]
+    ObjError(#[from] tobj::LoadError),
+    #[error("Recording failed: {0}")]
+    RecordingError(String),
 }
 
 pub type Result<T> = std::result::Result<T, ViewerError>;
 pub type Transform = na::Matrix4<f32>;
+/// Predicate on frame names deciding which frames get rendered.
+pub type RenderFilter = Box<dyn Fn(&str) -> bool + Send>;
+/// Callback run on the render thread for every event of the viewer's window.
+pub type EventHandler = Box<dyn FnMut(&WindowEvent<'_>) + Send>;
+
+/// One of the three axes of a frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    fn index(self) -> usize {
+        match self {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        }
+    }
+}
+
+const HEADLESS_FRAME_TIME: Duration = Duration::from_millis(16); // About 60 frames per second
+const TRAIL_COLOR: [f32; 4] = [0.4, 0.4, 0.4, 1.0]; // Mid gray
+const TETHER_COLOR: [f32; 4] = [0.5, 0.5, 0.5, 0.6]; // Faint gray
+const LABEL_COLOR: [f32; 4] = [0.2, 0.2, 0.2, 1.0]; // Dark gray
+const LABEL_SIZE: f32 = 10.0; // Height of frame name labels in pixels
+const GRID_TICK_LINES: i32 = 5; // Grid lines between two tick labels
+const FPS_OVERLAY_MARGIN: f32 = 10.0; // Pixels from the top-left corner of the window
+const UPDATE_PULSE_GAIN: f32 = 1.0; // Extra axis length right after an update, relative to normal
+const PICK_RADIUS: i32 = 8; // Pixels from a frame's axes within which a click selects it
+const CLICK_TOLERANCE: f64 = 3.0; // Pixels the cursor may move between press and release of a click
+const GHOST_ALPHA: f32 = 0.35; // Opacity multiplier of ghost frames
+const REPLY_POLL_INTERVAL: Duration = Duration::from_millis(100); // Between checks that a viewer waited on still runs
+
+/// Requests applied by the render thread, which owns the window.
+pub(crate) enum Command {
+    MoveToMonitor(usize),
+    PushFrame(String, Transform),
+    RemoveFrame(String),
+    ClearFrames,
+    Screenshot(Screenshot),
+    PickFrame(Pick),
+}
+
+/// Request for the frame drawn under a window pixel, answered once the next
+/// image is drawn.
+pub(crate) struct Pick {
+    x: f32,
+    y: f32,
+    reply: Sender<Option<String>>,
+}
+
+/// Work on the next drawn image, queued by other threads.
+pub(crate) enum DrawRequest {
+    Screenshot(Screenshot),
+    Pick(Pick),
+}
+
+/// Text drawn at a world position.
+struct Text {
+    position: [f32; 3],
+    text: String,
+    size: f32, // Height in pixels
+    color: [f32; 4],
+}
+
+/// Filled rectangle in the XY plane of a frame.
+struct Plane {
+    transform: Transform,
+    width: f32,
+    height: f32,
+    color: [f32; 4],
+}
+
+/// Vertices (position and color) of a point cloud or of line segments.
+struct Geometry {
+    vertices: Vec<f32>,
+    updated: Instant,
+}
+
+impl Geometry {
+    fn new(points: impl Iterator<Item = [f32; 3]>, color: [f32; 4]) -> Self {
+        let vertices = points.flat_map(|point| point.into_iter().chain(color)).collect();
+        Geometry {
+            vertices,
+            updated: Instant::now(),
+        }
+    }
+}
+
+/// Triangle mesh drawn with a frame's transform.
+struct Mesh {
+    vertices: Vec<f32>, // Position and color, in the frame's coordinates
+    indices: Vec<u32>,  // Three per triangle
+    updated: Instant,
+}
+
+/// Path traced by a frame's origin, keeping its latest `max_points` positions.
+struct Trail {
+    max_points: usize,
+    geometry: Geometry,
+}
+
+impl Trail {
+    /// Extend the trail to `point` unless the origin has not moved, dropping the oldest points.
+    fn extend(&mut self, point: [f32; 3]) {
+        let vertices = &mut self.geometry.vertices;
+        if vertices.len() >= FLOATS_PER_VERTEX && vertices[vertices.len() - FLOATS_PER_VERTEX..][..3] == point {
+            return;
+        }
+        vertices.extend(point.into_iter().chain(TRAIL_COLOR));
+        let excess = (vertices.len() / FLOATS_PER_VERTEX).saturating_sub(self.max_points);
+        vertices.drain(..excess * FLOATS_PER_VERTEX);
+        self.geometry.updated = Instant::now();
+    }
+}
+
+/// Wireframe ellipsoid, e.g. showing a covariance.
+struct Ellipsoid {
+    transform: Transform, // Maps the unit sphere onto the ellipsoid
+    color: [f32; 4],
+}
+
+/// Appearance of a single frame, kept across updates of its transform.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameStyle {
+    /// Length of the axes in meters, scaled along with the viewer's
+    /// [axis length](Viewer::set_axis_length) when it is changed from 0.1
+    pub axis_length: f32,
+    /// Width of the axis lines in pixels, or `None` for the viewer's frame line width
+    pub line_width: Option<f32>,
+    /// Colors of the X, Y and Z axes, replacing the default (or name based) colors
+    pub colors: Option<[[f32; 4]; 3]>,
+    /// Opacity multiplier
+    pub alpha: f32,
+    /// Color of the dot drawn at the origin when origin markers are shown
+    pub marker_color: [f32; 4],
+}
+
+impl Default for FrameStyle {
+    fn default() -> Self {
+        FrameStyle {
+            axis_length: AXIS_LENGTH,
+            line_width: None,
+            colors: None,
+            alpha: 1.0,
+            marker_color: [0.0, 0.0, 0.0, 1.0],
+        }
+    }
+}
+
+/// Ongoing interpolation of a frame's transform.
+#[derive(Clone)]
+struct Tween {
+    from: Transform,
+    to: Transform,
+    start: Instant,
+    duration: Duration,
+}
 
 #[derive(Clone)]
 struct Frame {
     transform: Transform,
+    updated: Instant,
+    style: FrameStyle,
+    tween: Option<Tween>,
+    visible: bool,
+    always_on_top: bool,
+    ghost: bool, // Faded and dashed, e.g. while its pose is stale
+    expires: Option<Instant>, // Removed by the render thread once past
+}
+
+impl Frame {
+    fn new(transform: Transform) -> Self {
+        Frame {
+            transform,
+            updated: Instant::now(),
+            style: FrameStyle::default(),
+            tween: None,
+            visible: true,
+            always_on_top: false,
+            ghost: false,
+            expires: None,
+        }
+    }
+
+    /// Whether the frame is drawn at `now`: visible and not hidden as stale.
+    fn is_drawn(&self, config: &ViewerConfig, now: Instant) -> bool {
+        let stale = config
+            .stale_hide
+            .is_some_and(|threshold| now.duration_since(self.updated) > threshold);
+        self.visible && !stale
+    }
+
+    /// Model transform of the frame's axes at `now`, scaled by its style and
+    /// any update pulse.
+    fn axes_transform(&self, config: &ViewerConfig, now: Instant) -> Transform {
+        // Enlarge recently pushed frames, shrinking back linearly over the pulse
+        let scale = config.update_pulse.map_or(1.0, |pulse| {
+            let progress = now.duration_since(self.updated).as_secs_f32() / pulse.as_secs_f32();
+            1.0 + UPDATE_PULSE_GAIN * (1.0 - progress).max(0.0)
+        });
+        let scale = scale * self.style.axis_length / AXIS_LENGTH;
+        self.transform * na::Matrix4::new_scaling(scale)
+    }
+}
+
+/// Tick labels along the axes of the world grid, every GRID_TICK_LINES lines
+/// from the origin (excluded), with their distance in meters.
+fn grid_ticks(grid: &GridConfig, world_up: [f32; 3]) -> Vec<(na::Point3<f32>, String)> {
+    if grid.step <= 0.0 {
+        return Vec::new();
+    }
+    let planes = grid.planes(world_up);
+    let lines = (grid.size / grid.step).round() as i32;
+
+    let mut ticks = Vec::new();
+    // An axis lies in every drawn plane whose normal is another axis
+    for axis in (0..3).filter(|&axis| (0..3).any(|normal| normal != axis && planes[normal])) {
+        for line in (GRID_TICK_LINES..=lines).step_by(GRID_TICK_LINES as usize) {
+            let distance = line as f32 * grid.step;
+            let mut position = na::Point3::origin();
+            position[axis] = distance;
+            let text = format!("{:.3}", distance).trim_end_matches('0').trim_end_matches('.').to_string();
+            ticks.push((position, text));
+        }
+    }
+    ticks
+}
+
+/// Reject transforms with non-finite values, which would blank the whole scene.
+fn check_transform(transform: &Transform, name: &str) -> Result<()> {
+    if transform.iter().all(|v| v.is_finite()) {
+        Ok(())
+    } else {
+        Err(ViewerError::InvalidTransform(name.to_string()))
+    }
+}
+
+/// Whether a window can be created with `samples` MSAA samples per pixel.
+fn is_valid_msaa_samples(samples: u16) -> bool {
+    samples == 0 || samples.is_power_of_two()
+}
+
+/// Insert a frame, or update the transform of an existing one while keeping its style.
+fn upsert_frame(frames: &mut HashMap<String, Frame>, name: &str, transform: Transform) {
+    match frames.get_mut(name) {
+        Some(frame) => {
+            frame.transform = transform;
+            frame.updated = Instant::now();
+            frame.tween = None;
+            frame.ghost = false;
+            frame.expires = None;
+        }
+        None => {
+            frames.insert(name.to_string(), Frame::new(transform));
+        }
+    }
 }
 
 pub struct Viewer {
     frames: Arc<RwLock<HashMap<String, Frame>>>,
     running: Arc<RwLock<bool>>,
+    playback: Arc<RwLock<Playback>>,
+    camera: Arc<RwLock<Camera>>,
+    config: Arc<RwLock<ViewerConfig>>,
+    render_filter: Arc<Mutex<Option<RenderFilter>>>,
+    event_handler: Arc<Mutex<Option<EventHandler>>>,
+    trajectories: Arc<RwLock<HashMap<String, Vec<[f32; 3]>>>>,
+    grids: Arc<RwLock<HashMap<String, (Transform, GridConfig)>>>,
+    texts: Arc<RwLock<HashMap<String, Text>>>,
+    ellipsoids: Arc<RwLock<HashMap<String, Ellipsoid>>>,
+    planes: Arc<RwLock<HashMap<String, Plane>>>,
+    points: Arc<RwLock<HashMap<String, Geometry>>>,
+    lines: Arc<RwLock<HashMap<String, Geometry>>>,
+    trails: Arc<RwLock<HashMap<String, Trail>>>,
+    meshes: Arc<RwLock<HashMap<String, Mesh>>>, // By frame name
+    clicked_frame: Arc<RwLock<Option<String>>>,
+    close_signals: Arc<Mutex<Vec<Sender<()>>>>,
+    render_thread: Arc<Mutex<Option<JoinHandle<()>>>>, // Spawned by start
+    world_origin: Arc<RwLock<Option<na::Vector3<f64>>>>, // Subtracted from double precision frames
+    recording: Arc<Mutex<Option<Recording>>>,
+    commands: Sender<Command>,
+    command_receiver: Arc<Mutex<Receiver<Command>>>,
+    monitors: Arc<RwLock<Vec<String>>>,
+    window_size: Arc<RwLock<(u32, u32)>>, // In physical pixels
+    stats: Arc<RwLock<RenderStats>>,
 }
 
 impl Viewer {
     pub fn new() -> Self {
+        Self::from_config(ViewerConfig::default())
+    }
+
+    /// Create a viewer that renders offscreen at `width` x `height` pixels
+    /// instead of opening a window, e.g. to take screenshots on a CI server.
+    ///
+    /// [`start`](Self::start) then creates a surfaceless OpenGL 3.3 context on
+    /// an EGL device (a GPU, or Mesa's software renderer), without connecting
+    /// to any X11 or Wayland display.
+    pub fn new_headless(width: u32, height: u32) -> Self {
+        Self::builder().headless(true).size(width, height).build()
+    }
+
+    /// Start building a viewer with non-default options.
+    pub fn builder() -> ViewerBuilder {
+        ViewerBuilder::new()
+    }
+
+    /// Create a viewer using the given configuration.
+    pub fn from_config(mut config: ViewerConfig) -> Self {
+        if !config.camera.is_valid(config.world_up) {
+            log::warn!("Invalid initial camera {:?}, falling back to the default", config.camera);
+            config.camera = CameraConfig::default();
+        }
+        if !config.grid.is_valid() {
+            log::warn!("Invalid grid {:?}, falling back to the default", config.grid);
+            config.grid = GridConfig::default();
+        }
+        // Window creation asserts on other sample counts
+        if !is_valid_msaa_samples(config.msaa_samples) {
+            let samples = 1 << config.msaa_samples.ilog2();
+            log::warn!("{} MSAA samples is not a power of two, using {}", config.msaa_samples, samples);
+            config.msaa_samples = samples;
+        }
+
+        let mut camera = Camera::new(config.width as f32 / config.height as f32);
+        camera.set_up(na::Vector3::from(config.world_up));
+        camera.look_at(config.camera.position.into(), config.camera.target.into());
+        camera.set_home();
+        camera.set_fov(config.camera.fov);
+        camera.set_projection_mode(config.camera.projection);
+        camera.set_handedness(config.handedness);
+        camera.set_zoom_mode(config.zoom_mode);
+        if config.mode_2d {
+            camera.enter_top_down(na::Vector3::from(config.world_up));
+        }
+
+        let window_size = (config.width, config.height);
+        let (commands, command_receiver) = mpsc::channel();
+
         Viewer {
             frames: Arc::new(RwLock::new(HashMap::new())),
             running: Arc::new(RwLock::new(false)),
+            playback: Arc::new(RwLock::new(Playback::new())),
+            camera: Arc::new(RwLock::new(camera)),
+            config: Arc::new(RwLock::new(config)),
+            render_filter: Arc::new(Mutex::new(None)),
+            event_handler: Arc::new(Mutex::new(None)),
+            trajectories: Arc::new(RwLock::new(HashMap::new())),
+            grids: Arc::new(RwLock::new(HashMap::new())),
+            texts: Arc::new(RwLock::new(HashMap::new())),
+            ellipsoids: Arc::new(RwLock::new(HashMap::new())),
+            planes: Arc::new(RwLock::new(HashMap::new())),
+            points: Arc::new(RwLock::new(HashMap::new())),
+            lines: Arc::new(RwLock::new(HashMap::new())),
+            trails: Arc::new(RwLock::new(HashMap::new())),
+            meshes: Arc::new(RwLock::new(HashMap::new())),
+            clicked_frame: Arc::new(RwLock::new(None)),
+            close_signals: Arc::new(Mutex::new(Vec::new())),
+            render_thread: Arc::new(Mutex::new(None)),
+            world_origin: Arc::new(RwLock::new(None)),
+            recording: Arc::new(Mutex::new(None)),
+            commands,
+            command_receiver: Arc::new(Mutex::new(command_receiver)),
+            monitors: Arc::new(RwLock::new(Vec::new())),
+            window_size: Arc::new(RwLock::new(window_size)),
+            stats: Arc::new(RwLock::new(RenderStats::default())),
         }
     }
 
+    /// The current configuration of the viewer.
+    pub fn config(&self) -> ViewerConfig {
+        self.config.read().clone()
+    }
+
+    /// Open the window and start rendering in a separate thread.
+    ///
+    /// Blocks until the window, its OpenGL context and the shaders are created,
+    /// and returns the error if that fails.
     pub fn start(&self) -> Result<()> {
-        let frames = self.frames.clone();
-        let running = self.running.clone();
-        *running.write() = true;
-
-        thread::spawn(move || {
-            let event_loop = EventLoopBuilder::new()
-                .with_any_thread(true)
-                .build();
-                
-            let window_builder = WindowBuilder::new()
-                .with_title("Frames Viewer")
-                .with_inner_size(winit::dpi::LogicalSize::new(800.0, 600.0));
-
-            let context = ContextBuilder::new()
-                .with_gl(GlRequest::Specific(Api::OpenGl, (3, 3)))
-                .with_vsync(false)
-                .build_windowed(window_builder, &event_loop)
-                .unwrap();
-
-            let context = unsafe { context.make_current().unwrap() };
-
-            gl::load_with(|symbol| context.get_proc_address(symbol) as *const _);
-
-            let mut renderer = Renderer::new();
-            
-            let mut left_mouse_pressed = false;
-            let mut middle_mouse_pressed = false;
-
-            event_loop.run(move |event, _, control_flow| {
-                *control_flow = ControlFlow::Poll;
-
-                match event {
-                    Event::WindowEvent { event, .. } => match event {
-                        WindowEvent::CloseRequested => {
-                            *control_flow = ControlFlow::Exit;
-                        }
-                        WindowEvent::Resized(physical_size) => {
-                            context.resize(physical_size);
-                            renderer.resize(physical_size.width, physical_size.height);
-                        }
-                        WindowEvent::MouseInput { button, state, .. } => {
-                            match button {
-                                MouseButton::Left => {
-                                    left_mouse_pressed = state == ElementState::Pressed;
-                                }
-                                MouseButton::Middle => {
-                                    middle_mouse_pressed = state == ElementState::Pressed;
+        let viewer = self.share();
+        *self.running.write() = true;
+        let (setup_sender, setup_receiver) = mpsc::channel();
+
+        let render_thread = thread::spawn(move || {
+            viewer.run_render_loop(true, setup_sender);
+            // Whatever stopped the render loop, nobody should wait for it any longer
+            viewer.reject_draw_requests();
+            viewer.notify_closed();
+        });
+        *self.render_thread.lock() = Some(render_thread);
+
+        setup_receiver.recv().unwrap_or_else(|_| {
+            *self.running.write() = false;
+            Err(ViewerError::ContextCreationError("Render thread exited during setup".to_string()))
+        })
+    }
+
+    /// Open the window on the calling thread and render until it is closed,
+    /// then exit the process.
+    ///
+    /// Unlike [`start`](Self::start), the event loop runs on the caller's thread,
+    /// which must be the main thread on platforms such as macOS. Take a
+    /// [`handle`](Self::handle) first to push frames from other threads.
+    pub fn run(self) -> ! {
+        *self.running.write() = true;
+        let (setup_sender, setup_receiver) = mpsc::channel();
+        self.run_render_loop(false, setup_sender);
+
+        if let Ok(Err(e)) = setup_receiver.try_recv() {
+            log::error!("Failed to start the viewer: {}", e);
+            std::process::exit(1);
+        }
+        std::process::exit(0);
+    }
+
+    /// Open the window (or headless context) and render until the viewer stops,
+    /// reporting through `setup_sender` whether the context could be created.
+    ///
+    /// `any_thread` allows the window's event loop to run off the main thread.
+    fn run_render_loop(&self, any_thread: bool, setup_sender: Sender<Result<()>>) {
+        let config = self.config.read().clone();
+        if config.headless {
+            // Without an event loop, which would need a display
+            self.run_headless(&config, setup_sender);
+        } else {
+            let mut event_loop = EventLoopBuilder::new().with_any_thread(any_thread).build();
+            self.run_event_loop(&mut event_loop, setup_sender);
+        }
+    }
+
+    /// Open the window and handle its events until the viewer stops.
+    fn run_event_loop(&self, event_loop: &mut EventLoop<()>, setup_sender: Sender<Result<()>>) {
+        let initial_config = self.config.read().clone();
+
+        let window_builder = WindowBuilder::new()
+            .with_title(&initial_config.title)
+            .with_inner_size(winit::dpi::LogicalSize::new(initial_config.width, initial_config.height))
+            .with_always_on_top(initial_config.always_on_top)
+            .with_decorations(initial_config.decorations);
+
+        let context = ContextBuilder::new()
+            .with_gl(GlRequest::Specific(Api::OpenGl, (3, 3)))
+            .with_vsync(initial_config.vsync)
+            .with_multisampling(initial_config.msaa_samples)
+            .build_windowed(window_builder, event_loop)
+            .map_err(|e| ViewerError::ContextCreationError(e.to_string()))
+            .and_then(|context| {
+                unsafe { context.make_current() }
+                    .map_err(|(_, e)| ViewerError::ContextCreationError(e.to_string()))
+            })
+            .and_then(|context| {
+                gl::load_with(|symbol| context.get_proc_address(symbol) as *const _);
+                let renderer = Renderer::new(&initial_config)?;
+                Ok((context, renderer))
+            });
+        let (context, mut renderer) = match context {
+            Ok(setup) => {
+                let _ = setup_sender.send(Ok(()));
+                setup
+            }
+            Err(e) => {
+                *self.running.write() = false;
+                let _ = setup_sender.send(Err(e));
+                return;
+            }
+        };
+
+        *self.monitors.write() = context
+            .window()
+            .available_monitors()
+            .enumerate()
+            .map(|(index, monitor)| monitor.name().unwrap_or_else(|| format!("Monitor {}", index)))
+            .collect();
+
+        let size = context.window().inner_size();
+        *self.window_size.write() = (size.width, size.height);
+
+        let mut left_mouse_pressed = false;
+        let mut middle_mouse_pressed = false;
+        let mut pan_mouse_pressed = false;
+        let mut cursor_position = winit::dpi::PhysicalPosition::new(0.0, 0.0);
+        let mut left_press_position = cursor_position;
+        let mut last_tick = Instant::now();
+
+        // Return from the loop instead of exiting the process, so that stopping
+        // the viewer only closes its window and frees the renderer
+        event_loop.run_return(|event, _, control_flow| {
+            *control_flow = ControlFlow::Poll;
+
+            // The application's handler sees each window event before the built-in controls
+            if let Event::WindowEvent { event, .. } = &event {
+                if let Some(handler) = self.event_handler.lock().as_mut() {
+                    handler(event);
+                }
+            }
+
+            match event {
+                Event::WindowEvent { event, .. } => match event {
+                    WindowEvent::CloseRequested => {
+                        *self.running.write() = false;
+                        self.notify_closed();
+                        *control_flow = ControlFlow::Exit;
+                    }
+                    WindowEvent::KeyboardInput {
+                        input: KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(key),
+                            ..
+                        },
+                        ..
+                    } if Some(key) == initial_config.close_key => {
+                        *self.running.write() = false;
+                        self.notify_closed();
+                        *control_flow = ControlFlow::Exit;
+                    }
+                    WindowEvent::Resized(physical_size) => {
+                        context.resize(physical_size);
+                        renderer.resize(physical_size.width, physical_size.height);
+                        self.camera.write().set_aspect(physical_size.width as f32 / physical_size.height as f32);
+                        *self.window_size.write() = (physical_size.width, physical_size.height);
+                    }
+                    WindowEvent::CursorMoved { position, .. } => {
+                        cursor_position = position;
+                    }
+                    WindowEvent::MouseInput { button, state, .. } => {
+                        match button {
+                            MouseButton::Left => {
+                                left_mouse_pressed = state == ElementState::Pressed;
+                                if left_mouse_pressed {
+                                    left_press_position = cursor_position;
+                                } else {
+                                    // A click rather than the end of an orbit selects the frame under the cursor
+                                    let (dx, dy) = (cursor_position.x - left_press_position.x, cursor_position.y - left_press_position.y);
+                                    if dx.hypot(dy) <= CLICK_TOLERANCE {
+                                        let picked = self.pick(&mut renderer, cursor_position.x as f32, cursor_position.y as f32);
+                                        *self.clicked_frame.write() = picked;
+                                    }
                                 }
-                                _ => (),
                             }
+                            MouseButton::Middle => {
+                                middle_mouse_pressed = state == ElementState::Pressed;
+                            }
+                            _ => (),
                         }
-                        WindowEvent::MouseWheel { delta, .. } => {
-                            let scroll_amount = match delta {
-                                MouseScrollDelta::LineDelta(_, y) => y * 2.0,
-                                MouseScrollDelta::PixelDelta(pos) => pos.y as f32 * 0.01,
-                            };
-                            renderer.camera_mut().zoom(scroll_amount);
-                        }
-                        _ => (),
-                    },
-                    Event::DeviceEvent { event: DeviceEvent::MouseMotion { delta }, .. } => {
-                        if left_mouse_pressed {
-                            renderer.camera_mut().orbit(delta.0 as f32 * 0.01, delta.1 as f32 * 0.01);
-                        } else if middle_mouse_pressed {
-                            renderer.camera_mut().pan(-delta.0 as f32 * 0.08, delta.1 as f32 * 0.08);
+                        if button == self.config.read().pan_button {
+                            pan_mouse_pressed = state == ElementState::Pressed;
                         }
                     }
-                    Event::MainEventsCleared => {
-                        // Clear the screen once before rendering all frames
-                        renderer.clear();
-                        
-                        // First render all other frames
-                        for frame in frames.read().values() {
-                            renderer.render(&frame.transform);
-                        }
-                        
-                        // Then render the origin frame last so it's always on top
-                        renderer.render(&Transform::identity());
-                        
-                        context.swap_buffers().unwrap();
+                    WindowEvent::KeyboardInput {
+                        input: KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(VirtualKeyCode::R),
+                            ..
+                        },
+                        ..
+                    } => {
+                        self.reset_camera();
+                    }
+                    WindowEvent::KeyboardInput {
+                        input: KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(VirtualKeyCode::F),
+                            ..
+                        },
+                        ..
+                    } => {
+                        self.fit_view();
+                    }
+                    WindowEvent::KeyboardInput {
+                        input: KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(VirtualKeyCode::H),
+                            ..
+                        },
+                        ..
+                    } => {
+                        let mut config = self.config.write();
+                        config.presentation_mode = !config.presentation_mode;
+                    }
+                    WindowEvent::KeyboardInput {
+                        input: KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(VirtualKeyCode::O),
+                            ..
+                        },
+                        ..
+                    } if !self.config.read().mode_2d => {
+                        self.camera.write().toggle_projection();
+                    }
+                    WindowEvent::KeyboardInput {
+                        input: KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(key @ (VirtualKeyCode::Numpad1 | VirtualKeyCode::Numpad3 | VirtualKeyCode::Numpad7)),
+                            ..
+                        },
+                        ..
+                    } if !self.config.read().mode_2d => {
+                        let view = match key {
+                            VirtualKeyCode::Numpad1 => StandardView::Front,
+                            VirtualKeyCode::Numpad3 => StandardView::Side,
+                            _ => StandardView::Top,
+                        };
+                        self.camera.write().set_standard_view(view);
+                    }
+                    WindowEvent::KeyboardInput {
+                        input: KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(key @ (VirtualKeyCode::Left | VirtualKeyCode::Right | VirtualKeyCode::Up | VirtualKeyCode::Down)),
+                            ..
+                        },
+                        ..
+                    } if !self.config.read().mode_2d => {
+                        let step = self.config.read().key_orbit_step;
+                        let (delta_x, delta_y) = match key {
+                            VirtualKeyCode::Left => (-step, 0.0),
+                            VirtualKeyCode::Right => (step, 0.0),
+                            VirtualKeyCode::Up => (0.0, -step),
+                            _ => (0.0, step),
+                        };
+                        self.camera.write().orbit(delta_x, delta_y);
+                    }
+                    WindowEvent::KeyboardInput {
+                        input: KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(key @ (VirtualKeyCode::Plus | VirtualKeyCode::Equals | VirtualKeyCode::NumpadAdd
+                                | VirtualKeyCode::Minus | VirtualKeyCode::NumpadSubtract)),
+                            ..
+                        },
+                        ..
+                    } => {
+                        // Same scale as one mouse wheel notch
+                        let step = self.config.read().key_zoom_step * 2.0;
+                        let zoom_in = !matches!(key, VirtualKeyCode::Minus | VirtualKeyCode::NumpadSubtract);
+                        self.camera.write().zoom(if zoom_in { step } else { -step });
+                    }
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        let scroll_amount = match delta {
+                            MouseScrollDelta::LineDelta(_, y) => y * 2.0,
+                            MouseScrollDelta::PixelDelta(pos) => pos.y as f32 * 0.01,
+                        };
+                        self.camera.write().zoom(scroll_amount * self.config.read().zoom_sensitivity);
                     }
                     _ => (),
+                },
+                Event::DeviceEvent { event: DeviceEvent::MouseMotion { delta }, .. } => {
+                    // Not holding the config while locking the camera, which draw locks first
+                    let (mode_2d, orbit_sensitivity, pan_sensitivity) = {
+                        let config = self.config.read();
+                        (config.mode_2d, config.orbit_sensitivity, config.pan_sensitivity)
+                    };
+                    if left_mouse_pressed && !mode_2d {
+                        let sensitivity = orbit_sensitivity;
+                        self.camera.write().orbit(delta.0 as f32 * sensitivity, delta.1 as f32 * sensitivity);
+                    } else if left_mouse_pressed || middle_mouse_pressed || pan_mouse_pressed {
+                        let sensitivity = pan_sensitivity;
+                        self.camera.write().pan(-delta.0 as f32 * sensitivity, delta.1 as f32 * sensitivity);
+                    }
                 }
-            });
+                Event::MainEventsCleared => {
+                    if !*self.running.read() {
+                        *control_flow = ControlFlow::Exit;
+                        return;
+                    }
+
+                    let now = Instant::now();
+                    let dt = now.duration_since(last_tick).as_secs_f64();
+                    last_tick = now;
+
+                    let requests = self.update(dt, Some(context.window()));
+                    self.draw(&mut renderer, now);
+                    self.answer(&mut renderer, requests);
+                    self.record(&renderer, dt);
+                    let work = now.elapsed();
+                    self.stats.write().record(Duration::from_secs_f64(dt), work);
+
+                    if let Err(e) = context.swap_buffers() {
+                        log::error!("Failed to swap buffers: {}", e);
+                        *self.running.write() = false;
+                        *control_flow = ControlFlow::Exit;
+                    }
+
+                    // Idle long enough that drawing takes at most the allowed share of the time
+                    if let Some(usage) = self.config.read().max_cpu_usage {
+                        thread::sleep(work.mul_f32((1.0 - usage) / usage));
+                    }
+                }
+                _ => (),
+            }
+        });
+
+        // The renderer frees its GL objects, so it goes before the context
+        drop(renderer);
+        drop(context);
+    }
+
+    /// Render offscreen until the viewer is stopped, with the size of `config`.
+    fn run_headless(&self, config: &ViewerConfig, setup_sender: Sender<Result<()>>) {
+        let context = HeadlessContext::new().and_then(|context| {
+            gl::load_with(|symbol| context.get_proc_address(symbol));
+            let renderer = Renderer::new(config)?;
+            Ok((context, renderer))
         });
+        let (context, mut renderer) = match context {
+            Ok(setup) => {
+                let _ = setup_sender.send(Ok(()));
+                setup
+            }
+            Err(e) => {
+                *self.running.write() = false;
+                let _ = setup_sender.send(Err(e));
+                return;
+            }
+        };
+        renderer.set_offscreen(true);
+        let mut last_tick = Instant::now();
+
+        while *self.running.read() {
+            let now = Instant::now();
+            let dt = now.duration_since(last_tick).as_secs_f64();
+            last_tick = now;
+
+            let requests = self.update(dt, None);
+            self.draw(&mut renderer, now);
+            self.answer(&mut renderer, requests);
+            self.record(&renderer, dt);
+            let work = now.elapsed();
+            self.stats.write().record(Duration::from_secs_f64(dt), work);
+
+            // Nothing is presented, so there is no point drawing faster than a display would
+            let idle = match self.config.read().max_cpu_usage {
+                Some(usage) => work.mul_f32((1.0 - usage) / usage),
+                None => HEADLESS_FRAME_TIME.saturating_sub(work),
+            };
+            thread::sleep(idle);
+        }
+
+        // The renderer frees its GL objects, so it goes before the context
+        drop(renderer);
+        drop(context);
+    }
+
+    /// Render into a GL context owned by a host application instead of opening
+    /// a window, e.g. to show the viewer inside a Qt widget or an engine tool.
+    ///
+    /// `loader` resolves OpenGL function pointers, and `width` and `height` are
+    /// the initial size of the drawing area in physical pixels. Use this instead
+    /// of [`start`](Self::start); mouse controls are left to the host. Fails if
+    /// the viewer's shaders do not compile or link in the host's context.
+    ///
+    /// # Safety
+    ///
+    /// An OpenGL 3.3 context must be current on the calling thread, and stay
+    /// current whenever the returned renderer is used or dropped.
+    pub unsafe fn embed(
+        &self,
+        loader: impl FnMut(&'static str) -> *const std::ffi::c_void,
+        width: u32,
+        height: u32,
+    ) -> Result<EmbeddedRenderer> {
+        let config = self.config.read().clone();
+        let renderer = Renderer::new_in_current_context(&config, loader)?;
+        *self.running.write() = true;
+
+        let mut embedded = EmbeddedRenderer::new(self.share(), renderer);
+        embedded.resize(width, height);
+        Ok(embedded)
+    }
+
+    /// Another `Viewer` sharing all of this one's state, for the render thread.
+    fn share(&self) -> Viewer {
+        Viewer {
+            frames: self.frames.clone(),
+            running: self.running.clone(),
+            playback: self.playback.clone(),
+            camera: self.camera.clone(),
+            config: self.config.clone(),
+            render_filter: self.render_filter.clone(),
+            event_handler: self.event_handler.clone(),
+            trajectories: self.trajectories.clone(),
+            grids: self.grids.clone(),
+            texts: self.texts.clone(),
+            ellipsoids: self.ellipsoids.clone(),
+            planes: self.planes.clone(),
+            points: self.points.clone(),
+            lines: self.lines.clone(),
+            trails: self.trails.clone(),
+            meshes: self.meshes.clone(),
+            clicked_frame: self.clicked_frame.clone(),
+            close_signals: self.close_signals.clone(),
+            render_thread: self.render_thread.clone(),
+            world_origin: self.world_origin.clone(),
+            recording: self.recording.clone(),
+            commands: self.commands.clone(),
+            command_receiver: self.command_receiver.clone(),
+            monitors: self.monitors.clone(),
+            window_size: self.window_size.clone(),
+            stats: self.stats.clone(),
+        }
+    }
+
+    /// Apply queued commands and advance playback by `dt` seconds.
+    ///
+    /// Window commands are dropped when there is no `window`. Returns the
+    /// requests to [`answer`](Self::answer) once the next image is drawn.
+    fn update(&self, dt: f64, window: Option<&winit::window::Window>) -> Vec<DrawRequest> {
+        let mut requests = Vec::new();
+        for command in self.command_receiver.lock().try_iter() {
+            match command {
+                Command::MoveToMonitor(index) => {
+                    let Some(window) = window else {
+                        continue;
+                    };
+                    if let Some(monitor) = window.available_monitors().nth(index) {
+                        // Center the window on the monitor
+                        let size = window.outer_size();
+                        let position = monitor.position();
+                        let x = position.x + (monitor.size().width as i32 - size.width as i32) / 2;
+                        let y = position.y + (monitor.size().height as i32 - size.height as i32) / 2;
+                        window.set_outer_position(winit::dpi::PhysicalPosition::new(x, y));
+                    }
+                }
+                Command::PushFrame(name, transform) => {
+                    upsert_frame(&mut self.frames.write(), &name, transform);
+                }
+                Command::RemoveFrame(name) => {
+                    self.frames.write().remove(&name);
+                }
+                Command::ClearFrames => self.frames.write().clear(),
+                Command::Screenshot(screenshot) => requests.push(DrawRequest::Screenshot(screenshot)),
+                Command::PickFrame(pick) => requests.push(DrawRequest::Pick(pick)),
+            }
+        }
+
+        // Advance frames moving towards a new transform
+        {
+            let now = Instant::now();
+            let mut frames = self.frames.write();
+            frames.retain(|_, frame| frame.expires.is_none_or(|expires| expires > now));
+            for frame in frames.values_mut() {
+                if let Some(tween) = &frame.tween {
+                    let t = now.duration_since(tween.start).as_secs_f32() / tween.duration.as_secs_f32();
+                    frame.transform = interpolate(&tween.from, &tween.to, t.min(1.0));
+                    if t >= 1.0 {
+                        frame.tween = None;
+                    }
+                }
+            }
+        }
+
+        // Advance the recorded sequence and apply its current frame set
+        {
+            let mut playback = self.playback.write();
+            if playback.advance(dt) {
+                let mut frames = self.frames.write();
+                let current = playback.current_frames();
+                for name in playback.frame_names() {
+                    match current.and_then(|current| current.get(name)) {
+                        Some(transform) => upsert_frame(&mut frames, name, *transform),
+                        None => {
+                            frames.remove(name);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Extend the trails of frames that moved
+        {
+            let frames = self.frames.read();
+            for (name, trail) in self.trails.write().iter_mut() {
+                if let Some(frame) = frames.get(name) {
+                    trail.extend([frame.transform[(0, 3)], frame.transform[(1, 3)], frame.transform[(2, 3)]]);
+                }
+            }
+        }
+
+        requests
+    }
+
+    /// Add what the renderer last drew, `dt` seconds after the previous image,
+    /// to the recording in progress if any.
+    fn record(&self, renderer: &Renderer, dt: f64) {
+        if let Some(recording) = self.recording.lock().as_mut() {
+            recording.capture(renderer, dt);
+        }
+    }
+
+    /// Save the screenshots and answer the picks requested for the image
+    /// `renderer` last drew.
+    fn answer(&self, renderer: &mut Renderer, requests: Vec<DrawRequest>) {
+        for request in requests {
+            match request {
+                DrawRequest::Screenshot(screenshot) => screenshot.save(renderer),
+                DrawRequest::Pick(pick) => {
+                    // The requester may have given up waiting
+                    let _ = pick.reply.send(self.pick(renderer, pick.x, pick.y));
+                }
+            }
+        }
+    }
+
+    /// Name of the frame drawn closest to window pixel (`x`, `y`), found by
+    /// drawing the frames' IDs offscreen with `renderer`.
+    fn pick(&self, renderer: &mut Renderer, x: f32, y: f32) -> Option<String> {
+        let now = Instant::now();
+        // Locked in the same order as in draw
+        let camera = self.camera.read();
+        let config = self.config.read().clone();
+        let meshes = self.meshes.read();
+        let render_filter = self.render_filter.lock();
+        let frames = self.frames.read();
+
+        let (names, targets): (Vec<&String>, Vec<PickTarget>) = frames
+            .iter()
+            .filter(|(name, frame)| frame.is_drawn(&config, now) && render_filter.as_ref().is_none_or(|filter| filter(name)))
+            .map(|(name, frame)| {
+                let target = PickTarget {
+                    axes: frame.axes_transform(&config, now),
+                    line_width: frame.style.line_width.unwrap_or(config.frame_line_width),
+                    on_top: frame.always_on_top,
+                    mesh: meshes.contains_key(name).then_some((name.as_str(), frame.transform)),
+                };
+                (name, target)
+            })
+            .unzip();
+        let index = renderer.pick(&camera, &targets, x, y, PICK_RADIUS)?;
+        Some(names[index].clone())
+    }
+
+    /// Draw the scene into the renderer's target.
+    fn draw(&self, renderer: &mut Renderer, now: Instant) {
+        let camera = self.camera.read();
+        let config = self.config.read().clone();
+
+        // Clear the screen once before rendering all frames
+        renderer.set_render_scale(config.render_scale);
+        renderer.set_background(config.background);
+        renderer.set_world_up(config.world_up);
+        renderer.set_grid(&config.grid);
+        renderer.set_grid_visible(config.grid.visible && !config.presentation_mode);
+        renderer.set_grid_line_width(config.grid_line_width);
+        renderer.set_axis_length(config.axis_length);
+        renderer.begin_frame();
+
+        {
+            let grids = self.grids.read();
+            renderer.retain_grids(|name| grids.contains_key(name));
+            for (name, (transform, grid)) in grids.iter() {
+                renderer.sync_grid(name, transform, grid);
+            }
+        }
+        if !config.presentation_mode {
+            renderer.render_grids(&camera);
+        }
+        
+        let meshes = self.meshes.read();
+        renderer.retain_meshes(|name| meshes.contains_key(name));
+        for (name, mesh) in meshes.iter() {
+            renderer.sync_mesh(name, &mesh.vertices, &mesh.indices, mesh.updated);
+        }
+
+        // First render all other frames
+        let render_filter = self.render_filter.lock();
+        let mut tethers = Vec::new();
+        let mut labels = Vec::new();
+        let mut markers = Vec::new();
+        let mut on_top = Vec::new();
+        for (name, frame) in self.frames.read().iter() {
+            if frame.is_drawn(&config, now) && render_filter.as_ref().is_none_or(|filter| filter(name)) {
+                if config.origin_tethers && !config.presentation_mode {
+                    let origin = frame.transform.fixed_view::<3, 1>(0, 3).into_owned();
+                    tethers.push((na::Point3::from(origin), na::Point3::origin()));
+                }
+
+                // Halve the opacity every half-life since the last update
+                let fade = config.age_fade.map_or(1.0, |half_life| {
+                    0.5f32.powf(now.duration_since(frame.updated).as_secs_f32() / half_life.as_secs_f32())
+                });
+                let ghost = if frame.ghost { GHOST_ALPHA } else { 1.0 };
+                let alpha = frame.style.alpha * fade * ghost;
+                if meshes.contains_key(name) {
+                    renderer.render_mesh(&camera, name, &frame.transform, alpha);
+                }
+                let origin = na::Point3::from(frame.transform.fixed_view::<3, 1>(0, 3).into_owned());
+                if config.show_labels {
+                    labels.push((name.clone(), origin, alpha));
+                }
+                if config.origin_markers {
+                    let [r, g, b, a] = frame.style.marker_color;
+                    markers.push((origin, [r, g, b, a * alpha]));
+                }
+                let colors = frame.style.colors.or_else(|| {
+                    config.color_by_name.then(|| color::name_colors(name, config.color_seed))
+                });
+                let transform = frame.axes_transform(&config, now);
+                let style = AxesStyle {
+                    alpha,
+                    colors,
+                    line_width: frame.style.line_width.unwrap_or(config.frame_line_width),
+                    on_top: frame.always_on_top,
+                    dashed: frame.ghost,
+                };
+                if style.on_top {
+                    // Drawn once everything else is, so nothing covers them
+                    on_top.push((transform, style));
+                } else {
+                    renderer.render(&camera, &transform, &style);
+                }
+            }
+        }
+        
+        {
+            let trajectories = self.trajectories.read();
+            renderer.retain_trajectories(|name| trajectories.contains_key(name));
+            for (name, points) in trajectories.iter() {
+                renderer.sync_trajectory(name, points);
+            }
+        }
+        renderer.render_trajectories(&camera);
+
+        {
+            let (points, lines, trails) = (self.points.read(), self.lines.read(), self.trails.read());
+            renderer.retain_geometries(|primitive, name| match primitive {
+                Primitive::Points => points.contains_key(name),
+                Primitive::Lines => lines.contains_key(name),
+                Primitive::LineStrip => trails.contains_key(name),
+            });
+            for (name, geometry) in points.iter() {
+                renderer.sync_geometry(Primitive::Points, name, &geometry.vertices, geometry.updated);
+            }
+            for (name, geometry) in lines.iter() {
+                renderer.sync_geometry(Primitive::Lines, name, &geometry.vertices, geometry.updated);
+            }
+            for (name, trail) in trails.iter() {
+                let geometry = &trail.geometry;
+                renderer.sync_geometry(Primitive::LineStrip, name, &geometry.vertices, geometry.updated);
+            }
+        }
+        renderer.render_geometries(&camera);
+
+        for ellipsoid in self.ellipsoids.read().values() {
+            renderer.render_ellipsoid(&camera, &ellipsoid.transform, ellipsoid.color);
+        }
+
+        // Translucent planes last among the scene geometry, so what they cover still shows
+        for plane in self.planes.read().values() {
+            renderer.render_plane(&camera, &plane.transform, plane.width, plane.height, plane.color);
+        }
+
+        if !markers.is_empty() {
+            renderer.render_markers(&camera, &markers);
+        }
+
+        if !tethers.is_empty() {
+            renderer.render_dashed_lines(&camera, &tethers, TETHER_COLOR);
+        }
+
+        // Then render the origin frame
+        if !config.presentation_mode {
+            let origin = Transform::new_scaling(config.origin_axis_length / config.axis_length);
+            let style = AxesStyle {
+                line_width: config.frame_line_width,
+                ..AxesStyle::default()
+            };
+            renderer.render(&camera, &origin, &style);
+        }
+
+        for (transform, style) in on_top {
+            renderer.render(&camera, &transform, &style);
+        }
+
+        for text in self.texts.read().values() {
+            renderer.render_text(&camera, &text.position.into(), &text.text, text.size, text.color);
+        }
+
+        for (name, origin, alpha) in labels {
+            let [r, g, b, a] = LABEL_COLOR;
+            renderer.render_text(&camera, &origin, &name, LABEL_SIZE, [r, g, b, a * alpha]);
+        }
+
+        if let Some(label) = &config.origin_label {
+            renderer.render_text(&camera, &na::Point3::origin(), label, LABEL_SIZE, LABEL_COLOR);
+        }
+
+        if config.grid_ticks && config.grid.visible && !config.presentation_mode {
+            for (position, text) in grid_ticks(&config.grid, config.world_up) {
+                renderer.render_text(&camera, &position, &text, LABEL_SIZE, LABEL_COLOR);
+            }
+        }
+
+        if config.scale_bar && !config.presentation_mode {
+            renderer.render_scale_bar(&camera);
+        }
+
+        if config.gnomon && !config.presentation_mode {
+            renderer.render_gnomon(&camera);
+        }
 
+        if config.fps_overlay {
+            let stats = *self.stats.read();
+            let text = format!("{:.0} FPS {:.1} ms", stats.fps, stats.frame_time.as_secs_f64() * 1000.0);
+            let (x, y) = (FPS_OVERLAY_MARGIN, FPS_OVERLAY_MARGIN + LABEL_SIZE);
+            renderer.render_screen_text(x, y, &text, LABEL_SIZE, LABEL_COLOR);
+        }
+
+        renderer.end_frame();
+    }
+
+    /// Add a frame or update an existing one.
+    ///
+    /// Fails without touching the frame if `transform` contains NaN or infinite values.
+    pub fn push_frame(&self, transform: Transform, name: &str) -> Result<()> {
+        check_transform(&transform, name)?;
+        upsert_frame(&mut self.frames.write(), name, transform);
         Ok(())
     }
 
-    pub fn push_frame(&self, transform: Transform, name: &str) {
-        let frame = Frame { transform };
-        self.frames.write().insert(name.to_string(), frame);
+    /// Add or update a frame that is removed once `ttl` has elapsed without it
+    /// being pushed again, e.g. for a sensor that may stop publishing.
+    ///
+    /// Pushing the frame with [`push_frame`](Self::push_frame) makes it permanent again.
+    pub fn push_frame_ttl(&self, transform: Transform, name: &str, ttl: Duration) -> Result<()> {
+        check_transform(&transform, name)?;
+        let mut frames = self.frames.write();
+        upsert_frame(&mut frames, name, transform);
+        if let Some(frame) = frames.get_mut(name) {
+            frame.expires = Some(Instant::now() + ttl);
+        }
+        Ok(())
     }
 
-    pub fn clear_frames(&self) {
-        self.frames.write().clear();
+    /// Add or update a frame from a rotation matrix and a translation in meters,
+    /// as with [`push_frame`](Self::push_frame).
+    pub fn push_frame_rt(&self, rotation: na::Matrix3<f32>, translation: na::Vector3<f32>, name: &str) -> Result<()> {
+        let mut transform = Transform::identity();
+        transform.fixed_view_mut::<3, 3>(0, 0).copy_from(&rotation);
+        transform.fixed_view_mut::<3, 1>(0, 3).copy_from(&translation);
+        self.push_frame(transform, name)
     }
 
-    pub fn stop(&self) {
-        *self.running.write() = false;
+    /// Add or update a frame from a double precision transform, e.g. in UTM
+    /// coordinates millions of meters from the origin, where `f32` would make
+    /// frames jitter.
+    ///
+    /// The frame is stored relative to the [world origin](Self::set_world_origin),
+    /// set to the translation of the first frame pushed this way unless given,
+    /// and only then converted to `f32`, so the scene around that origin keeps
+    /// full precision on the GPU. Other geometry (points, lines, grids...) must
+    /// be given relative to the same origin.
+    pub fn push_frame_f64(&self, transform: na::Matrix4<f64>, name: &str) -> Result<()> {
+        if !transform.iter().all(|v| v.is_finite()) {
+            return Err(ViewerError::InvalidTransform(name.to_string()));
+        }
+        let translation = transform.fixed_view::<3, 1>(0, 3).into_owned();
+        let origin = *self.world_origin.write().get_or_insert(translation);
+        let mut rebased = transform;
+        rebased.fixed_view_mut::<3, 1>(0, 3).copy_from(&(translation - origin));
+        self.push_frame(rebased.cast::<f32>(), name)
     }
-}
 
-impl Default for Viewer {
-    fn default() -> Self {
-        Self::new()
+    /// Set the point, in double precision world coordinates, that frames pushed
+    /// with [`push_frame_f64`](Self::push_frame_f64) are stored relative to.
+    ///
+    /// Frames already pushed are not moved, so set it before pushing any.
+    pub fn set_world_origin(&self, origin: [f64; 3]) {
+        *self.world_origin.write() = Some(na::Vector3::from(origin));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// World origin of double precision frames, if one is set.
+    pub fn world_origin(&self) -> Option<[f64; 3]> {
+        self.world_origin.read().map(|origin| origin.into())
+    }
 
-    #[test]
-    fn test_create_viewer() {
-        let viewer = Viewer::new();
-        assert_eq!(*viewer.running.read(), false);
+    /// Add or update a frame from a rigid transform (SE(3) pose), as with
+    /// [`push_frame`](Self::push_frame).
+    pub fn push_isometry(&self, isometry: na::Isometry3<f32>, name: &str) -> Result<()> {
+        self.push_frame(isometry.to_homogeneous(), name)
     }
 
-    #[test]
-    fn test_push_frame() {
-        let viewer = Viewer::new();
-        let transform = Transform::identity();
-        viewer.push_frame(transform, "test_frame");
-        assert_eq!(viewer.frames.read().len(), 1);
+    /// Add or update many frames at once.
+    ///
+    /// All frames are written under a single lock, so the renderer never draws
+    /// a partially applied batch. Fails without pushing any frame if a transform
+    /// contains NaN or infinite values.
+    pub fn push_frames(&self, frames: &[(Transform, String)]) -> Result<()> {
+        for (transform, name) in frames {
+            check_transform(transform, name)?;
+        }
+        let mut current = self.frames.write();
+        for (transform, name) in frames {
+            upsert_frame(&mut current, name, *transform);
+        }
+        Ok(())
+    }
+
+    /// Make `frames` the only frames in the viewer, in one step.
+    ///
+    /// Unlike [`clear_frames`](Self::clear_frames) followed by pushes, the renderer
+    /// never sees the viewer empty or half updated. Frames that stay keep their
+    /// style and visibility. Fails without changing anything if a transform
+    /// contains NaN or infinite values.
+    pub fn replace_frames(&self, frames: &[(Transform, String)]) -> Result<()> {
+        for (transform, name) in frames {
+            check_transform(transform, name)?;
+        }
+        let mut current = self.frames.write();
+        current.retain(|name, _| frames.iter().any(|(_, new)| new == name));
+        for (transform, name) in frames {
+            upsert_frame(&mut current, name, *transform);
+        }
+        Ok(())
+    }
+
+    /// Push a frame whose axes are `scale` times the default length of 10 centimeters,
+    /// or of the [axis length](Self::set_axis_length) if changed.
+    ///
+    /// The scale is kept when the frame is later updated with [`push_frame`](Self::push_frame).
+    /// Fails without touching the frame if `transform` contains NaN or infinite values.
+    pub fn push_frame_scaled(&self, transform: Transform, name: &str, scale: f32) -> Result<()> {
+        check_transform(&transform, name)?;
+        let mut frames = self.frames.write();
+        upsert_frame(&mut frames, name, transform);
+        if let Some(frame) = frames.get_mut(name) {
+            frame.style.axis_length = AXIS_LENGTH * scale;
+        }
+        Ok(())
+    }
+
+    /// Push a frame drawn with `colors` (RGBA) for its X, Y and Z axes instead of red, green and blue.
+    ///
+    /// The colors are kept when the frame is later updated with [`push_frame`](Self::push_frame).
+    /// Fails without touching the frame if `transform` contains NaN or infinite values.
+    pub fn push_frame_colored(&self, transform: Transform, name: &str, colors: [[f32; 4]; 3]) -> Result<()> {
+        check_transform(&transform, name)?;
+        let mut frames = self.frames.write();
+        upsert_frame(&mut frames, name, transform);
+        if let Some(frame) = frames.get_mut(name) {
+            frame.style.colors = Some(colors);
+        }
+        Ok(())
+    }
+
+    /// Move a frame smoothly from its current transform to `target` over `duration`.
+    ///
+    /// The translation is interpolated linearly and the rotation spherically,
+    /// which hides the jumps of poses updated at a low rate. A frame that does
+    /// not exist yet is added at `target` directly. Fails without touching the
+    /// frame if `target` contains NaN or infinite values.
+    pub fn push_frame_interpolated(&self, name: &str, target: Transform, duration: Duration) -> Result<()> {
+        check_transform(&target, name)?;
+        let mut frames = self.frames.write();
+        match frames.get_mut(name) {
+            Some(frame) if !duration.is_zero() => {
+                frame.updated = Instant::now();
+                frame.tween = Some(Tween {
+                    from: frame.transform,
+                    to: target,
+                    start: frame.updated,
+                    duration,
+                });
+            }
+            _ => upsert_frame(&mut frames, name, target),
+        }
+        Ok(())
+    }
+
+    /// Set the appearance of a frame, kept when it is pushed again.
+    ///
+    /// Returns false if the frame does not exist.
+    pub fn style_frame(&self, name: &str, style: FrameStyle) -> bool {
+        match self.frames.write().get_mut(name) {
+            Some(frame) => {
+                frame.style = style;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Change only the opacity multiplier of a frame's style, clamped to [0, 1],
+    /// e.g. to fade out older frames of a trail.
+    ///
+    /// Returns false if the frame does not exist.
+    pub fn set_frame_alpha(&self, name: &str, alpha: f32) -> bool {
+        match self.frames.write().get_mut(name) {
+            Some(frame) => {
+                frame.style.alpha = alpha.clamp(0.0, 1.0);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Hide or show a frame without removing it, keeping its transform, style,
+    /// trail and mesh. Pushing the frame again does not change its visibility.
+    ///
+    /// Returns false if the frame does not exist.
+    pub fn set_frame_visible(&self, name: &str, visible: bool) -> bool {
+        match self.frames.write().get_mut(name) {
+            Some(frame) => {
+                frame.visible = visible;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Draw a frame over everything else in the scene, even what is in front
+    /// of it, e.g. to highlight a selected frame. Pushing the frame again does
+    /// not change this.
+    ///
+    /// Returns false if the frame does not exist.
+    pub fn set_frame_always_on_top(&self, name: &str, on_top: bool) -> bool {
+        match self.frames.write().get_mut(name) {
+            Some(frame) => {
+                frame.always_on_top = on_top;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Show a frame as a ghost of its last known pose, faded with dashed axes,
+    /// e.g. when its data goes stale instead of removing it. Pushing the frame
+    /// again makes it live again.
+    ///
+    /// Returns false if the frame does not exist.
+    pub fn set_frame_ghost(&self, name: &str, ghost: bool) -> bool {
+        match self.frames.write().get_mut(name) {
+            Some(frame) => {
+                frame.ghost = ghost;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove a frame. Returns whether it existed.
+    pub fn remove_frame(&self, name: &str) -> bool {
+        self.frames.write().remove(name).is_some()
+    }
+
+    /// Handle for sending frame updates from other threads or async tasks
+    /// without locking the viewer.
+    pub fn handle(&self) -> ViewerHandle {
+        ViewerHandle::new(self.commands.clone())
+    }
+
+    /// Rename a frame, keeping its transform and metadata.
+    ///
+    /// Returns false (and leaves the frames untouched) if `old` does not exist
+    /// or another frame is already named `new`.
+    pub fn rename_frame(&self, old: &str, new: &str) -> bool {
+        let mut frames = self.frames.write();
+        if old == new {
+            return frames.contains_key(old);
+        }
+        if frames.contains_key(new) {
+            return false;
+        }
+        match frames.remove(old) {
+            Some(frame) => {
+                frames.insert(new.to_string(), frame);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Angle in radians between `a_axis` of frame `a` and `b_axis` of frame `b`,
+    /// both taken in world coordinates.
+    ///
+    /// Returns `None` if either frame does not exist.
+    pub fn axis_angle(&self, a: &str, a_axis: Axis, b: &str, b_axis: Axis) -> Option<f32> {
+        let frames = self.frames.read();
+        let direction = |name: &str, axis: Axis| {
+            frames
+                .get(name)
+                .map(|frame| frame.transform.fixed_view::<3, 1>(0, axis.index()).into_owned())
+        };
+        Some(direction(a, a_axis)?.angle(&direction(b, b_axis)?))
+    }
+
+    /// Append a point (in meters) to the named trajectory, creating it if needed.
+    ///
+    /// Trajectories are drawn as polylines through their points. Only the new
+    /// point is uploaded to the GPU, so growing a long path every tick is cheap.
+    pub fn append_trajectory_point(&self, name: &str, point: [f32; 3]) {
+        self.trajectories.write().entry(name.to_string()).or_default().push(point);
+    }
+
+    /// Trace the path of a frame's origin as it moves, keeping its latest
+    /// `max_points` positions. Resets the trail if it was already enabled.
+    pub fn enable_trail(&self, name: &str, max_points: usize) {
+        let trail = Trail {
+            max_points,
+            geometry: Geometry::new(std::iter::empty(), TRAIL_COLOR),
+        };
+        self.trails.write().insert(name.to_string(), trail);
+    }
+
+    /// Stop tracing a frame's path and clear its trail. Returns whether it was enabled.
+    pub fn disable_trail(&self, name: &str) -> bool {
+        self.trails.write().remove(name).is_some()
+    }
+
+    /// Remove a trajectory. Returns whether it existed.
+    pub fn remove_trajectory(&self, name: &str) -> bool {
+        self.trajectories.write().remove(name).is_some()
+    }
+
+    /// Resize the world grid to `size` meters with lines every `step` meters.
+    ///
+    /// The grid's vertices are regenerated on the render thread at the next frame.
+    /// Returns false, leaving the grid unchanged, unless both are finite and
+    /// positive with at most 10000 lines along each axis.
+    pub fn set_grid(&self, size: f32, step: f32) -> bool {
+        let mut config = self.config.write();
+        let grid = GridConfig { size, step, ..config.grid.clone() };
+        if !grid.is_valid() {
+            return false;
+        }
+        config.grid = grid;
+        true
+    }
+
+    /// Draw the world grid in the given planes only, e.g. `GridPlanes::XY` for
+    /// just the floor of a Z-up scene.
+    pub fn set_grid_planes(&self, planes: GridPlanes) {
+        self.config.write().grid.mode = GridMode::Planes(planes);
+    }
+
+    /// Draw the world grid with solid or dashed lines.
+    pub fn set_grid_style(&self, style: GridStyle) {
+        self.config.write().grid.style = style;
+    }
+
+    /// Show or hide the world grid.
+    pub fn set_grid_visible(&self, visible: bool) {
+        self.config.write().grid.visible = visible;
+    }
+
+    /// Draw an additional grid anchored at `transform`, e.g. to show a second
+    /// reference frame next to the world one. Replaces any grid of the same name.
+    ///
+    /// Returns false, adding nothing, if `transform` is not finite or `config`
+    /// is not [valid](GridConfig::is_valid).
+    pub fn add_grid(&self, name: &str, transform: Transform, config: GridConfig) -> bool {
+        if check_transform(&transform, name).is_err() || !config.is_valid() {
+            return false;
+        }
+        self.grids.write().insert(name.to_string(), (transform, config));
+        true
+    }
+
+    /// Remove an anchored grid. Returns whether it existed.
+    pub fn remove_grid(&self, name: &str) -> bool {
+        self.grids.write().remove(name).is_some()
+    }
+
+    /// Draw `text` facing the camera at a world `position` (in meters), on top of the scene.
+    ///
+    /// `size` is the text height in pixels. Replaces any text of the same name.
+    pub fn push_text(&self, name: &str, position: [f32; 3], text: String, size: f32, color: [f32; 4]) {
+        let text = Text {
+            position,
+            text,
+            size,
+            color,
+        };
+        self.texts.write().insert(name.to_string(), text);
+    }
+
+    /// Remove a text. Returns whether it existed.
+    pub fn remove_text(&self, name: &str) -> bool {
+        self.texts.write().remove(name).is_some()
+    }
+
+    /// Draw the 1-sigma ellipsoid of a 3x3 position covariance (in square meters)
+    /// centered at `center`. Replaces any ellipsoid of the same name.
+    pub fn push_covariance(&self, name: &str, center: [f32; 3], covariance: na::Matrix3<f32>, color: [f32; 4]) {
+        let ellipsoid = Ellipsoid {
+            transform: covariance_transform(center.into(), covariance),
+            color,
+        };
+        self.ellipsoids.write().insert(name.to_string(), ellipsoid);
+    }
+
+    /// Remove a covariance ellipsoid. Returns whether it existed.
+    pub fn remove_covariance(&self, name: &str) -> bool {
+        self.ellipsoids.write().remove(name).is_some()
+    }
+
+    /// Draw a filled `width` x `height` rectangle (in meters) centered in the XY
+    /// plane of `transform`, e.g. a table top or a detected plane.
+    ///
+    /// Use an alpha below 1 in `color` to see through it. Replaces any plane of the same name.
+    pub fn push_plane(&self, name: &str, transform: Transform, width: f32, height: f32, color: [f32; 4]) {
+        let plane = Plane {
+            transform,
+            width,
+            height,
+            color,
+        };
+        self.planes.write().insert(name.to_string(), plane);
+    }
+
+    /// Remove a plane. Returns whether it existed.
+    pub fn remove_plane(&self, name: &str) -> bool {
+        self.planes.write().remove(name).is_some()
+    }
+
+    /// Draw a point cloud, e.g. a LiDAR scan, in a single `color`. Replaces any
+    /// point cloud of the same name.
+    pub fn push_points(&self, name: &str, points: &[[f32; 3]], color: [f32; 4]) {
+        let geometry = Geometry::new(points.iter().copied(), color);
+        self.points.write().insert(name.to_string(), geometry);
+    }
+
+    /// Remove a point cloud. Returns whether it existed.
+    pub fn remove_points(&self, name: &str) -> bool {
+        self.points.write().remove(name).is_some()
+    }
+
+    /// Draw line segments, given as (start, end) pairs, in a single `color`.
+    /// Replaces any set of segments of the same name.
+    pub fn push_lines(&self, name: &str, segments: &[([f32; 3], [f32; 3])], color: [f32; 4]) {
+        let geometry = Geometry::new(segments.iter().flat_map(|&(start, end)| [start, end]), color);
+        self.lines.write().insert(name.to_string(), geometry);
+    }
+
+    /// Remove a set of line segments. Returns whether it existed.
+    pub fn remove_lines(&self, name: &str) -> bool {
+        self.lines.write().remove(name).is_some()
+    }
+
+    /// Draw a triangle mesh in a single `color` with the transform of a frame,
+    /// e.g. the robot link the frame belongs to. Replaces any mesh attached to
+    /// the same frame, and is drawn once that frame is pushed.
+    ///
+    /// `vertices` are in the frame's coordinates, and each of `indices` is a
+    /// triangle. Fails if an index is out of bounds.
+    pub fn attach_mesh(&self, frame_name: &str, vertices: &[[f32; 3]], indices: &[[u32; 3]], color: [f32; 4]) -> Result<()> {
+        if let Some(index) = indices.iter().flatten().find(|&&index| index as usize >= vertices.len()) {
+            return Err(ViewerError::InvalidMesh(format!(
+                "index {} is out of bounds for {} vertices",
+                index,
+                vertices.len()
+            )));
+        }
+
+        let mesh = Mesh {
+            vertices: vertices.iter().flat_map(|vertex| vertex.iter().copied().chain(color)).collect(),
+            indices: indices.iter().flatten().copied().collect(),
+            updated: Instant::now(),
+        };
+        self.meshes.write().insert(frame_name.to_string(), mesh);
+        Ok(())
+    }
+
+    /// Load a Wavefront OBJ file and attach its triangles to a frame, as with
+    /// [`attach_mesh`](Self::attach_mesh). Materials are ignored.
+    pub fn attach_obj<P: AsRef<Path>>(&self, frame_name: &str, path: P, color: [f32; 4]) -> Result<()> {
+        let (models, _) = tobj::load_obj(path.as_ref(), &tobj::GPU_LOAD_OPTIONS)?;
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        for model in models {
+            let offset = vertices.len() as u32;
+            vertices.extend(model.mesh.positions.chunks_exact(3).map(|p| [p[0], p[1], p[2]]));
+            indices.extend(model.mesh.indices.chunks_exact(3).map(|t| [t[0] + offset, t[1] + offset, t[2] + offset]));
+        }
+        self.attach_mesh(frame_name, &vertices, &indices, color)
+    }
+
+    /// Remove the mesh attached to a frame. Returns whether there was one.
+    pub fn detach_mesh(&self, frame_name: &str) -> bool {
+        self.meshes.write().remove(frame_name).is_some()
+    }
+
+    /// Names of the frames currently in the viewer, in no particular order.
+    pub fn frame_names(&self) -> Vec<String> {
+        self.frames.read().keys().cloned().collect()
+    }
+
+    /// Current transform of a frame, if it exists, e.g. to read back frames pushed
+    /// from another part of the program.
+    pub fn get_frame(&self, name: &str) -> Option<Transform> {
+        self.frames.read().get(name).map(|frame| frame.transform)
+    }
+
+    /// Current transform of a frame in double precision world coordinates,
+    /// i.e. offset by the [world origin](Self::set_world_origin) if one is set.
+    pub fn get_frame_f64(&self, name: &str) -> Option<na::Matrix4<f64>> {
+        let mut transform = self.get_frame(name)?.cast::<f64>();
+        if let Some(origin) = *self.world_origin.read() {
+            let translation = transform.fixed_view::<3, 1>(0, 3) + origin;
+            transform.fixed_view_mut::<3, 1>(0, 3).copy_from(&translation);
+        }
+        Some(transform)
+    }
+
+    /// Time elapsed since the frame was last pushed, if it exists.
+    pub fn frame_age(&self, name: &str) -> Option<Duration> {
+        self.frames.read().get(name).map(|frame| frame.updated.elapsed())
+    }
+
+    /// Fade frames out as they age, halving their opacity every `half_life`.
+    ///
+    /// Passing `None` (or a zero half-life) disables fading.
+    pub fn set_age_fade(&self, half_life: Option<Duration>) {
+        self.config.write().age_fade = half_life.filter(|half_life| !half_life.is_zero());
+    }
+
+    /// Hide frames that have not been pushed for longer than `threshold`.
+    ///
+    /// Hidden frames are kept and show up again as soon as they are pushed.
+    /// Passing `None` shows all frames regardless of their age.
+    pub fn set_stale_hide(&self, threshold: Option<Duration>) {
+        self.config.write().stale_hide = threshold;
+    }
+
+    /// Briefly enlarge a frame's axes when it is pushed, shrinking back over `duration`.
+    ///
+    /// Passing `None` (or a zero duration) disables the pulse.
+    pub fn set_update_pulse(&self, duration: Option<Duration>) {
+        self.config.write().update_pulse = duration.filter(|duration| !duration.is_zero());
+    }
+
+    /// Remove every frame, leaving point clouds, lines, meshes and other overlays.
+    pub fn clear_frames(&self) {
+        self.frames.write().clear();
+    }
+
+    /// Remove every point cloud.
+    pub fn clear_points(&self) {
+        self.points.write().clear();
+    }
+
+    /// Remove every set of line segments.
+    pub fn clear_lines(&self) {
+        self.lines.write().clear();
+    }
+
+    /// Detach every mesh from its frame.
+    pub fn clear_meshes(&self) {
+        self.meshes.write().clear();
+    }
+
+    /// Empty the scene: frames and everything drawn alongside them (trajectories,
+    /// trails, anchored grids, texts, covariances, planes, points, lines and meshes).
+    /// The world grid and other settings are left untouched.
+    pub fn clear_all(&self) {
+        self.clear_frames();
+        self.trajectories.write().clear();
+        self.trails.write().clear();
+        self.grids.write().clear();
+        self.texts.write().clear();
+        self.ellipsoids.write().clear();
+        self.planes.write().clear();
+        self.clear_points();
+        self.clear_lines();
+        self.clear_meshes();
+    }
+
+    /// Save the frames whose name starts with `name_prefix` as a TUM trajectory file.
+    ///
+    /// Each frame becomes one `timestamp tx ty tz qx qy qz qw` line, ordered by
+    /// the time it was last pushed (in seconds since the Unix epoch).
+    pub fn save_trajectory_tum<P: AsRef<Path>>(&self, path: P, name_prefix: &str) -> Result<()> {
+        let now = SystemTime::now();
+        let mut poses: Vec<(f64, Transform)> = self
+            .frames
+            .read()
+            .iter()
+            .filter(|(name, _)| name.starts_with(name_prefix))
+            .map(|(_, frame)| {
+                let updated = now - frame.updated.elapsed();
+                let timestamp = updated.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+                (timestamp, frame.transform)
+            })
+            .collect();
+        poses.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        tum::write_tum(&mut writer, &poses)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load a recorded sequence of frame sets, one per timestamp (in seconds).
+    ///
+    /// Playback starts paused on the first sample; frames from the sequence
+    /// replace any frame of the same name while it is being played back.
+    pub fn load_sequence(&self, frames_over_time: Vec<Sample>) {
+        self.playback.write().load(frames_over_time);
+    }
+
+    pub fn play(&self) {
+        self.playback.write().play();
+    }
+
+    pub fn pause(&self) {
+        self.playback.write().pause();
+    }
+
+    /// Jump to the given time (in seconds) of the loaded sequence.
+    pub fn seek(&self, time: f64) {
+        self.playback.write().seek(time);
+    }
+
+    /// Set the playback speed multiplier (1.0 is real time).
+    pub fn set_speed(&self, speed: f64) {
+        self.playback.write().set_speed(speed);
+    }
+
+    /// Current time (in seconds) of the playback clock.
+    pub fn playback_time(&self) -> f64 {
+        self.playback.read().time()
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playback.read().is_playing()
+    }
+
+    /// Set the handedness of the coordinate system frames are expressed in.
+    ///
+    /// With [`Handedness::LeftHanded`] the whole scene (frames, grid and
+    /// everything else) is mirrored along the world Z axis at the view stage,
+    /// so data from left-handed engines such as Unity displays as it does there
+    /// without flipping every transform. The default is right-handed.
+    pub fn set_handedness(&self, handedness: Handedness) {
+        self.camera.write().set_handedness(handedness);
+        self.config.write().handedness = handedness;
+    }
+
+    /// Set the camera's vertical field of view in radians, clamped to about [1, 170] degrees.
+    pub fn set_fov(&self, fov: f32) {
+        self.camera.write().set_fov(fov);
+    }
+
+    /// The camera's vertical field of view in radians, which zooming changes in [`ZoomMode::Fov`].
+    pub fn fov(&self) -> f32 {
+        self.camera.read().fov()
+    }
+
+    /// Choose whether zooming moves the camera (the default) or changes its field of view.
+    pub fn set_zoom_mode(&self, mode: ZoomMode) {
+        self.camera.write().set_zoom_mode(mode);
+        self.config.write().zoom_mode = mode;
+    }
+
+    /// Distances (in meters) from the camera beyond which geometry is clipped,
+    /// 0.1 and 100 by default.
+    ///
+    /// Depth precision depends on the ratio `far / near`, and mostly on `near`:
+    /// when overlapping lines flicker (z-fighting), raise `near` as much as the
+    /// closest geometry allows, e.g. to 1 for a scene spanning kilometers, and keep
+    /// `far` just beyond the farthest. Returns false, leaving the planes
+    /// unchanged, unless `0 < near < far`.
+    pub fn set_clip_planes(&self, near: f32, far: f32) -> bool {
+        self.camera.write().set_clip_planes(near, far)
+    }
+
+    /// Near and far clipping distances in meters.
+    pub fn clip_planes(&self) -> (f32, f32) {
+        self.camera.read().clip_planes()
+    }
+
+    /// Closest and farthest distances (in meters) to the target that zooming can reach.
+    ///
+    /// Returns false, leaving the limits unchanged, unless `0 < min_distance <= max_distance`.
+    pub fn set_zoom_limits(&self, min_distance: f32, max_distance: f32) -> bool {
+        self.camera.write().set_zoom_limits(min_distance, max_distance)
+    }
+
+    /// Mouse button panning the camera when dragged (right by default), in addition
+    /// to the middle one. The left button always orbits.
+    pub fn set_pan_button(&self, button: MouseButton) {
+        self.config.write().pan_button = button;
+    }
+
+    /// How far the arrow keys orbit the camera (in radians) and `+` and `-` zoom it
+    /// (in mouse wheel notches) per press.
+    pub fn set_keyboard_steps(&self, orbit: f32, zoom: f32) {
+        let mut config = self.config.write();
+        config.key_orbit_step = orbit;
+        config.key_zoom_step = zoom;
+    }
+
+    /// Switch between perspective and orthographic projection. Pressing O in the
+    /// window toggles between the two.
+    ///
+    /// In orthographic mode zooming changes the visible height instead of moving the camera.
+    /// Ignored in [2D mode](Self::set_2d_mode), which is always orthographic.
+    pub fn set_projection_mode(&self, mode: ProjectionMode) {
+        let mut camera = self.camera.write();
+        if !self.config.read().mode_2d {
+            camera.set_projection_mode(mode);
+        }
+    }
+
+    pub fn projection_mode(&self) -> ProjectionMode {
+        self.camera.read().projection_mode()
+    }
+
+    /// Place the camera at `position`, looking at (and orbiting around) `target`,
+    /// with `up` pointing up on screen. Coordinates are in meters.
+    ///
+    /// Returns false, leaving the camera unchanged, if the pose is not finite or
+    /// the view direction is parallel to `up`. In [2D mode](Self::set_2d_mode)
+    /// the camera then looks straight down at `target` from the same distance.
+    pub fn set_camera_pose(&self, position: [f32; 3], target: [f32; 3], up: [f32; 3]) -> bool {
+        let direction = na::Vector3::from(target) - na::Vector3::from(position);
+        let finite = position.iter().chain(&target).chain(&up).all(|v| v.is_finite());
+        if !finite || direction.cross(&na::Vector3::from(up)).norm() < 1e-6 {
+            return false;
+        }
+        let mut camera = self.camera.write();
+        camera.set_up(up.into());
+        camera.look_at(position.into(), target.into());
+        self.keep_top_down(&mut camera);
+        true
+    }
+
+    /// Look at the camera target from the front, side or top, keeping the distance
+    /// to it. Pressing 1, 3 or 7 on the numeric keypad does the same. Ignored in
+    /// [2D mode](Self::set_2d_mode), which always looks from the top.
+    pub fn set_standard_view(&self, view: StandardView) {
+        let mut camera = self.camera.write();
+        if !self.config.read().mode_2d {
+            camera.set_standard_view(view);
+        }
+    }
+
+    /// Look straight down again after the camera was moved, if in 2D mode.
+    ///
+    /// Takes the locked camera, since the config must be locked after it.
+    fn keep_top_down(&self, camera: &mut Camera) {
+        let config = self.config.read();
+        if config.mode_2d {
+            camera.enter_top_down(na::Vector3::from(config.world_up));
+        }
+    }
+
+    /// Switch to a top-down view for planar data: the camera looks straight down
+    /// at the ground plane through an orthographic projection, and dragging with
+    /// any button pans instead of orbiting. Turning it off restores the previous
+    /// view, keeping the current target.
+    pub fn set_2d_mode(&self, enabled: bool) {
+        // Camera before config, in the same order as the render thread
+        let mut camera = self.camera.write();
+        let mut config = self.config.write();
+        if config.mode_2d == enabled {
+            return;
+        }
+        config.mode_2d = enabled;
+        if enabled {
+            camera.enter_top_down(na::Vector3::from(config.world_up));
+        } else {
+            camera.leave_top_down();
+        }
+    }
+
+    /// Set the world up direction, e.g. `[0.0, 1.0, 0.0]` for Y-up data instead
+    /// of the default Z-up (REP-103). The camera orbits around it and the grid
+    /// is drawn on the plane perpendicular to it.
+    ///
+    /// Returns false, leaving the viewer unchanged, if `up` is zero or not finite.
+    pub fn set_world_up(&self, up: [f32; 3]) -> bool {
+        let vector = na::Vector3::from(up);
+        if !up.iter().all(|v| v.is_finite()) || vector.norm() == 0.0 {
+            return false;
+        }
+        let mut camera = self.camera.write();
+        self.config.write().world_up = up;
+        camera.set_world_up(vector);
+        self.keep_top_down(&mut camera);
+        true
+    }
+
+    /// Current camera position, target and up direction, as taken by
+    /// [`set_camera_pose`](Self::set_camera_pose).
+    pub fn camera_pose(&self) -> ([f32; 3], [f32; 3], [f32; 3]) {
+        let camera = self.camera.read();
+        (camera.position().into(), camera.target().into(), camera.up().into())
+    }
+
+    /// Orbit around a frame's origin instead of the current target, keeping the
+    /// camera's distance and orientation. Returns whether the frame exists.
+    pub fn focus_on_frame(&self, name: &str) -> bool {
+        let Some(transform) = self.frames.read().get(name).map(|frame| frame.transform) else {
+            return false;
+        };
+        let origin = transform.fixed_view::<3, 1>(0, 3).into_owned();
+        self.camera.write().focus(na::Point3::from(origin));
+        true
+    }
+
+    /// Point the camera at everything in the scene, from its current direction and
+    /// just close enough to see it all: frames (including their axes), point
+    /// clouds, lines and meshes. Pressing F in the window does the same.
+    ///
+    /// Returns false, leaving the camera unchanged, if the scene is empty.
+    pub fn fit_view(&self) -> bool {
+        let mut points = Vec::new();
+        let axis_scale = self.config.read().axis_length / AXIS_LENGTH;
+        {
+            let frames = self.frames.read();
+            let meshes = self.meshes.read();
+            for (name, frame) in frames.iter().filter(|(_, frame)| frame.visible) {
+                points.push(frame.transform.transform_point(&na::Point3::origin()));
+                for axis in 0..3 {
+                    let tip = na::Point3::from(na::Vector3::ith(axis, frame.style.axis_length * axis_scale));
+                    points.push(frame.transform.transform_point(&tip));
+                }
+                if let Some(mesh) = meshes.get(name) {
+                    let vertices = mesh.vertices.chunks_exact(FLOATS_PER_VERTEX);
+                    points.extend(vertices.map(|v| frame.transform.transform_point(&na::Point3::new(v[0], v[1], v[2]))));
+                }
+            }
+        }
+        for geometry in self.points.read().values().chain(self.lines.read().values()) {
+            let vertices = geometry.vertices.chunks_exact(FLOATS_PER_VERTEX);
+            points.extend(vertices.map(|v| na::Point3::new(v[0], v[1], v[2])));
+        }
+        let Some(first) = points.first() else {
+            return false;
+        };
+
+        let (min, max) = points.iter().fold((*first, *first), |(min, max), point| {
+            (min.inf(point), max.sup(point))
+        });
+        let center = na::center(&min, &max);
+        let radius = (max - min).norm() / 2.0;
+        self.camera.write().fit(center, radius.max(AXIS_LENGTH));
+        true
+    }
+
+    /// Move the camera back to its initial viewpoint. Pressing R in the window does the same.
+    ///
+    /// In [2D mode](Self::set_2d_mode) the camera looks straight down at the
+    /// initial target instead.
+    pub fn reset_camera(&self) {
+        let mut camera = self.camera.write();
+        camera.reset();
+        self.keep_top_down(&mut camera);
+    }
+
+    /// The view matrix the viewer is currently rendering with.
+    pub fn view_matrix(&self) -> Transform {
+        self.camera.read().view_matrix()
+    }
+
+    /// The projection matrix the viewer is currently rendering with.
+    pub fn projection_matrix(&self) -> Transform {
+        self.camera.read().projection_matrix()
+    }
+
+    /// Window pixel coordinates (origin at the top-left) of a frame's origin.
+    ///
+    /// Returns `None` if the frame does not exist, or its origin is behind the
+    /// camera or outside the window.
+    pub fn project_to_screen(&self, name: &str) -> Option<(f32, f32)> {
+        let origin = self.frames.read().get(name)?.transform.fixed_view::<3, 1>(0, 3).into_owned();
+        let (width, height) = *self.window_size.read();
+        self.camera.read().project(&na::Point3::from(origin), width, height)
+    }
+
+    /// Name of the frame drawn closest to window pixel (`x`, `y`) (origin at the
+    /// top-left), if one is within a few pixels of it.
+    ///
+    /// Frames are told apart by drawing them offscreen in colors encoding their
+    /// IDs, so a frame hidden behind a mesh is not picked. Blocks until the
+    /// render thread has drawn the next image, and returns `None` if the viewer
+    /// is not running or stops first. Must not be called from an event handler, which runs on
+    /// the render thread.
+    pub fn pick_frame(&self, x: f32, y: f32) -> Option<String> {
+        if !self.is_running() {
+            return None;
+        }
+        let (reply, response) = mpsc::channel();
+        let _ = self.commands.send(Command::PickFrame(Pick { x, y, reply }));
+        self.wait_for_reply(response).flatten()
+    }
+
+    /// Name of the frame selected by the last left click in the window, or `None`
+    /// if that click was not on a frame (or there was none yet).
+    pub fn clicked_frame(&self) -> Option<String> {
+        self.clicked_frame.read().clone()
+    }
+
+    /// Color each frame's axes by a hash of its name instead of red/green/blue.
+    ///
+    /// The X, Y and Z axes get decreasing shades of the same hue.
+    pub fn set_color_by_name(&self, enabled: bool) {
+        self.config.write().color_by_name = enabled;
+    }
+
+    /// Set the seed of the name to color hash.
+    ///
+    /// The mapping is stable for a given seed; trying another seed helps when
+    /// two important frames end up with similar colors.
+    pub fn set_color_seed(&self, seed: u64) {
+        self.config.write().color_seed = seed;
+    }
+
+    /// Set the default length in meters of the frame axes, 0.1 by default.
+    ///
+    /// Frames pushed with a scale or a style keep their size relative to this
+    /// default; the world origin axes are set separately with
+    /// [`set_origin_axis_length`](Self::set_origin_axis_length). Returns false,
+    /// leaving the length unchanged, unless it is positive.
+    pub fn set_axis_length(&self, length: f32) -> bool {
+        if !(length > 0.0 && length.is_finite()) {
+            return false;
+        }
+        self.config.write().axis_length = length;
+        true
+    }
+
+    /// Set the length in meters of the world origin axes, independently of other frames.
+    pub fn set_origin_axis_length(&self, length: f32) {
+        self.config.write().origin_axis_length = length;
+    }
+
+    /// Width in pixels of the frame axes (3 by default) and of the grid lines (1 by default).
+    ///
+    /// Frames styled with their own line width keep it. OpenGL core profiles only
+    /// guarantee 1 pixel wide lines: drivers clamp wider ones to the largest width
+    /// they support, which is commonly large enough on desktop GPUs.
+    pub fn set_line_widths(&self, frame: f32, grid: f32) {
+        let mut config = self.config.write();
+        config.frame_line_width = frame;
+        config.grid_line_width = grid;
+    }
+
+    /// Color (RGBA, each in `[0, 1]`) the window is cleared to, light gray by default.
+    pub fn set_background_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.config.write().background = [r, g, b, a];
+    }
+
+    /// Render at `scale` times the window resolution and downsample to the window.
+    ///
+    /// A scale of 2.0 supersamples every pixel 2x2, removing aliasing on lines
+    /// at the cost of rendering four times as many pixels. The scale is clamped
+    /// to `[0.25, 4.0]`.
+    pub fn set_render_scale(&self, scale: f32) {
+        self.config.write().render_scale = scale.clamp(0.25, 4.0);
+    }
+
+    /// Draw a faint dashed line from every frame origin to the world origin.
+    pub fn set_origin_tethers(&self, visible: bool) {
+        self.config.write().origin_tethers = visible;
+    }
+
+    /// Hide the grids, origin axes, origin tethers, scale bar and axis indicator, leaving only
+    /// frames, trajectories and texts on the background. Handy for screenshots.
+    ///
+    /// The individual settings are kept and apply again once this is turned off.
+    /// Pressing H in the window toggles it too.
+    pub fn set_presentation_mode(&self, enabled: bool) {
+        self.config.write().presentation_mode = enabled;
+    }
+
+    /// Draw a dot at each frame's origin, which is easier to spot than the axes
+    /// from afar. Its color is set per frame with [`style_frame`](Self::style_frame).
+    pub fn set_origin_markers(&self, visible: bool) {
+        self.config.write().origin_markers = visible;
+    }
+
+    /// Draw `label` at the world origin, e.g. "world", or nothing with `None`.
+    pub fn set_origin_label(&self, label: Option<&str>) {
+        self.config.write().origin_label = label.map(str::to_string);
+    }
+
+    /// Label the world grid's axes with their distance from the origin, every
+    /// five grid lines (every 50 centimeters with the default grid).
+    pub fn set_grid_ticks(&self, visible: bool) {
+        self.config.write().grid_ticks = visible;
+    }
+
+    /// Show or hide the name of each frame next to its origin (shown by default).
+    pub fn set_show_labels(&self, visible: bool) {
+        self.config.write().show_labels = visible;
+    }
+
+    /// Show or hide the 1 meter scale bar in the bottom-left corner of the window.
+    pub fn set_scale_bar(&self, visible: bool) {
+        self.config.write().scale_bar = visible;
+    }
+
+    /// Show or hide the axis indicator in the bottom-right corner of the window,
+    /// which turns with the camera to show the orientation of the world axes.
+    pub fn set_gnomon(&self, visible: bool) {
+        self.config.write().gnomon = visible;
+    }
+
+    /// Only render frames whose name matches the given predicate.
+    ///
+    /// Filtered-out frames are kept in the viewer, just not drawn.
+    /// Passing `None` renders every frame again.
+    pub fn set_render_filter(&self, filter: Option<RenderFilter>) {
+        *self.render_filter.lock() = filter;
+    }
+
+    /// Run `handler` on the render thread for every window event (resizes, key
+    /// presses, ...) before the viewer's own controls handle it, e.g. to add
+    /// application shortcuts. Passing `None` removes the handler.
+    pub fn set_event_handler(&self, handler: Option<EventHandler>) {
+        *self.event_handler.lock() = handler;
+    }
+
+    /// Names of the monitors connected when the window was opened.
+    ///
+    /// Empty until the viewer is started.
+    pub fn list_monitors(&self) -> Vec<String> {
+        self.monitors.read().clone()
+    }
+
+    /// Move the window to the center of the monitor at `index` in [`list_monitors`](Self::list_monitors).
+    ///
+    /// Returns false if there is no such monitor.
+    pub fn move_to_monitor(&self, index: usize) -> bool {
+        if index >= self.monitors.read().len() {
+            return false;
+        }
+        let _ = self.commands.send(Command::MoveToMonitor(index));
+        true
+    }
+
+    /// Restore the default look: background, world grid, axis colors and
+    /// overlays, and drop every frame's style. Transforms are left untouched.
+    pub fn reset_appearance(&self) {
+        let defaults = ViewerConfig::default();
+        {
+            let mut config = self.config.write();
+            config.background = defaults.background;
+            config.grid = defaults.grid;
+            config.frame_line_width = defaults.frame_line_width;
+            config.grid_line_width = defaults.grid_line_width;
+            config.color_by_name = defaults.color_by_name;
+            config.color_seed = defaults.color_seed;
+            config.axis_length = defaults.axis_length;
+            config.origin_axis_length = defaults.origin_axis_length;
+            config.origin_tethers = defaults.origin_tethers;
+            config.presentation_mode = defaults.presentation_mode;
+            config.scale_bar = defaults.scale_bar;
+            config.gnomon = defaults.gnomon;
+            config.show_labels = defaults.show_labels;
+            config.origin_markers = defaults.origin_markers;
+            config.origin_label = defaults.origin_label;
+            config.grid_ticks = defaults.grid_ticks;
+            config.age_fade = defaults.age_fade;
+            config.update_pulse = defaults.update_pulse;
+        }
+        for frame in self.frames.write().values_mut() {
+            frame.style = FrameStyle::default();
+        }
+    }
+
+    /// Frame rate and timing of the render loop.
+    pub fn render_stats(&self) -> RenderStats {
+        *self.stats.read()
+    }
+
+    /// Time between the last two rendered frames, or `None` before the first one is drawn.
+    pub fn last_frame_time(&self) -> Option<Duration> {
+        Some(self.stats.read().last_interval).filter(|interval| !interval.is_zero())
+    }
+
+    /// Draw the frame rate and frame time in the top-left corner of the window.
+    pub fn set_fps_overlay(&self, visible: bool) {
+        self.config.write().fps_overlay = visible;
+    }
+
+    /// Lower the frame rate so that drawing takes at most `usage` (in (0, 1])
+    /// of the render thread's time. `None` draws as fast as possible.
+    pub fn set_max_cpu_usage(&self, usage: Option<f32>) {
+        self.config.write().max_cpu_usage = usage.map(|usage| usage.clamp(0.01, 1.0));
+    }
+
+    /// Save the next rendered image as a PNG file, at the render resolution.
+    ///
+    /// Blocks until the render thread has written the file, failing with
+    /// [`ViewerError::NotRunning`] if the viewer stops first.
+    pub fn screenshot<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        if !self.is_running() {
+            return Err(ViewerError::NotRunning);
+        }
+        let (reply, response) = mpsc::channel();
+        let screenshot = Screenshot {
+            path: path.as_ref().to_path_buf(),
+            reply,
+        };
+        let _ = self.commands.send(Command::Screenshot(screenshot));
+        self.wait_for_reply(response).unwrap_or(Err(ViewerError::NotRunning))
+    }
+
+    /// Start recording the rendered images to a video file at `fps` frames per second.
+    ///
+    /// Images are encoded by `ffmpeg`, which must be on the `PATH`, in the format
+    /// given by the extension of `path` (e.g. `.mp4` or `.gif`). The video runs at
+    /// `fps` whatever the render rate: images are skipped or repeated as needed.
+    /// The render size must not change while recording. A recording already in
+    /// progress is finished first, and its error returned if it failed.
+    pub fn start_recording<P: AsRef<Path>>(&self, path: P, fps: f32) -> Result<()> {
+        let recording = Recording::new(path.as_ref().to_path_buf(), fps)?;
+        let previous = self.recording.lock().replace(recording);
+        match previous {
+            Some(previous) => previous.finish(),
+            None => Ok(()),
+        }
+    }
+
+    /// Stop recording and wait until the video file is written.
+    ///
+    /// Returns the first error met while recording. Does nothing when not recording.
+    pub fn stop_recording(&self) -> Result<()> {
+        let recording = self.recording.lock().take();
+        match recording {
+            Some(recording) => recording.finish(),
+            None => Ok(()),
+        }
+    }
+
+    /// Receiver getting a message once the viewer stops: as soon as the user
+    /// closes the window, before the render loop exits, or when the render
+    /// thread ends for any other reason. Lets producer threads shut down
+    /// without polling [`is_running`](Self::is_running).
+    pub fn close_signal(&self) -> Receiver<()> {
+        let (sender, receiver) = mpsc::channel();
+        self.close_signals.lock().push(sender);
+        receiver
+    }
+
+    /// Drop the queued requests waiting for an image that will not be drawn,
+    /// which closes their reply channels. Other commands are still applied.
+    fn reject_draw_requests(&self) {
+        drop(self.update(0.0, None));
+    }
+
+    /// Wait for the render thread to answer through `response`, giving up
+    /// with `None` once the viewer is stopped.
+    fn wait_for_reply<T>(&self, response: Receiver<T>) -> Option<T> {
+        loop {
+            match response.recv_timeout(REPLY_POLL_INTERVAL) {
+                Ok(reply) => return Some(reply),
+                Err(RecvTimeoutError::Timeout) if self.is_running() => {}
+                Err(_) => return None,
+            }
+        }
+    }
+
+    /// Fire every pending close signal, once.
+    fn notify_closed(&self) {
+        for signal in self.close_signals.lock().drain(..) {
+            // The receiver may have been dropped
+            let _ = signal.send(());
+        }
+    }
+
+    /// Block until the render thread spawned by [`start`](Self::start) exits,
+    /// i.e. until the window is closed or [`stop`](Self::stop) is called, so
+    /// that `main` does not return and kill it.
+    ///
+    /// Returns immediately if the viewer was not started.
+    pub fn wait(&self) {
+        // Holding the lock makes other waiters block until the thread is joined
+        let mut render_thread = self.render_thread.lock();
+        if let Some(render_thread) = render_thread.take() {
+            if render_thread.join().is_err() {
+                log::error!("The render thread panicked");
+            }
+        }
+    }
+
+    /// Close the window and stop the render loop.
+    pub fn stop(&self) {
+        *self.running.write() = false;
+    }
+
+    /// Whether the viewer is started and its window has not been closed.
+    pub fn is_running(&self) -> bool {
+        *self.running.read()
+    }
+}
+
+impl Default for Viewer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Transform a fraction `t` of the way from `from` to `to`, interpolating the
+/// translation linearly and the rotation spherically.
+fn interpolate(from: &Transform, to: &Transform, t: f32) -> Transform {
+    let isometry = |transform: &Transform| {
+        let rotation = na::Rotation3::from_matrix(&transform.fixed_view::<3, 3>(0, 0).into_owned());
+        let translation = na::Translation3::from(transform.fixed_view::<3, 1>(0, 3).into_owned());
+        na::Isometry3::from_parts(translation, na::UnitQuaternion::from_rotation_matrix(&rotation))
+    };
+    // Rotations half a turn apart have no unique path, jump to the target then
+    isometry(from)
+        .try_lerp_slerp(&isometry(to), t, 1e-6)
+        .map_or(*to, |isometry| isometry.to_homogeneous())
+}
+
+/// Transform mapping the unit sphere onto the 1-sigma ellipsoid of `covariance`.
+///
+/// The ellipsoid axes are the eigenvectors of the covariance, scaled by the
+/// square roots of their eigenvalues.
+fn covariance_transform(center: na::Vector3<f32>, covariance: na::Matrix3<f32>) -> Transform {
+    let eigen = covariance.symmetric_eigen();
+    let scales = eigen.eigenvalues.map(|value| value.max(0.0).sqrt());
+    let axes = eigen.eigenvectors * na::Matrix3::from_diagonal(&scales);
+
+    let mut transform = Transform::identity();
+    transform.fixed_view_mut::<3, 3>(0, 0).copy_from(&axes);
+    transform.fixed_view_mut::<3, 1>(0, 3).copy_from(&center);
+    transform
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_viewer() {
+        let viewer = Viewer::new();
+        assert_eq!(*viewer.running.read(), false);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = ViewerConfig {
+            title: "Test".to_string(),
+            background: [0.0, 0.0, 0.0, 1.0],
+            ..ViewerConfig::default()
+        };
+        let viewer = Viewer::from_config(config.clone());
+        assert_eq!(viewer.config(), config);
+    }
+
+    #[test]
+    fn test_remove_frame() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        viewer.push_frame(Transform::identity(), "b").unwrap();
+
+        assert!(viewer.remove_frame("a"));
+        assert!(!viewer.remove_frame("a"));
+        assert!(!viewer.frames.read().contains_key("a"));
+        assert!(viewer.frames.read().contains_key("b"));
+    }
+
+    #[test]
+    fn test_push_frame_scaled() {
+        let viewer = Viewer::new();
+        viewer.push_frame_scaled(Transform::identity(), "a", 2.0).unwrap();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        assert_eq!(viewer.frames.read()["a"].style.axis_length, 2.0 * AXIS_LENGTH);
+    }
+
+    #[test]
+    fn test_push_frame_colored() {
+        let viewer = Viewer::new();
+        let colors = [[1.0, 0.5, 0.0, 1.0], [0.0, 0.5, 1.0, 1.0], [0.5, 0.5, 0.5, 1.0]];
+        viewer.push_frame_colored(Transform::identity(), "a", colors).unwrap();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        assert_eq!(viewer.frames.read()["a"].style.colors, Some(colors));
+    }
+
+    #[test]
+    fn test_push_lines() {
+        let viewer = Viewer::new();
+        let color = [1.0, 0.0, 0.0, 1.0];
+        viewer.push_lines("a", &[([0.0, 0.0, 0.0], [1.0, 2.0, 3.0])], color);
+
+        let lines = viewer.lines.read();
+        assert_eq!(lines["a"].vertices, [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 2.0, 3.0, 1.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_attach_mesh() {
+        let viewer = Viewer::new();
+        let vertices = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        viewer.attach_mesh("a", &vertices, &[[0, 1, 2]], [1.0; 4]).unwrap();
+        assert_eq!(viewer.meshes.read()["a"].indices, [0, 1, 2]);
+
+        let result = viewer.attach_mesh("b", &vertices, &[[0, 1, 3]], [1.0; 4]);
+        assert!(matches!(result, Err(ViewerError::InvalidMesh(_))));
+        assert!(viewer.detach_mesh("a"));
+        assert!(!viewer.detach_mesh("b"));
+    }
+
+    #[test]
+    fn test_trail() {
+        let viewer = Viewer::new();
+        viewer.enable_trail("a", 2);
+        for x in [1.0, 2.0, 2.0, 3.0] {
+            viewer.push_frame(Transform::new_translation(&na::Vector3::new(x, 0.0, 0.0)), "a").unwrap();
+            viewer.update(0.0, None);
+        }
+
+        let trails = viewer.trails.read();
+        let xs: Vec<f32> = trails["a"].geometry.vertices.chunks(FLOATS_PER_VERTEX).map(|vertex| vertex[0]).collect();
+        assert_eq!(xs, [2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_push_frame_rt() {
+        let viewer = Viewer::new();
+        let rotation = na::Rotation3::from_axis_angle(&na::Vector3::z_axis(), 0.5);
+        let translation = na::Vector3::new(1.0, 2.0, 3.0);
+        viewer.push_frame_rt(*rotation.matrix(), translation, "a").unwrap();
+
+        let expected = na::Isometry3::from_parts(translation.into(), rotation.into()).to_homogeneous();
+        assert_eq!(viewer.get_frame("a"), Some(expected));
+    }
+
+    #[test]
+    fn test_push_isometry() {
+        let viewer = Viewer::new();
+        let isometry = na::Isometry3::new(na::Vector3::new(1.0, 2.0, 3.0), na::Vector3::new(0.0, 0.0, 0.5));
+        viewer.push_isometry(isometry, "a").unwrap();
+        assert_eq!(viewer.get_frame("a"), Some(isometry.to_homogeneous()));
+    }
+
+    #[test]
+    fn test_push_frame_f64() {
+        let viewer = Viewer::new();
+        let utm = |x: f64, y: f64| na::Matrix4::new_translation(&na::Vector3::new(x, y, 10.0));
+        viewer.push_frame_f64(utm(500_000.25, 4_649_776.5), "a").unwrap();
+        viewer.push_frame_f64(utm(500_000.5, 4_649_776.75), "b").unwrap();
+
+        // The first frame sets the origin, the second keeps its sub-millimeter offset
+        assert_eq!(viewer.world_origin(), Some([500_000.25, 4_649_776.5, 10.0]));
+        assert_eq!(viewer.get_frame("a"), Some(Transform::identity()));
+        let b = viewer.get_frame("b").unwrap();
+        assert_eq!(b.fixed_view::<3, 1>(0, 3), na::Vector3::new(0.25, 0.25, 0.0));
+        assert_eq!(viewer.get_frame_f64("b"), Some(utm(500_000.5, 4_649_776.75)));
+
+        assert!(viewer.push_frame_f64(utm(f64::NAN, 0.0), "c").is_err());
+    }
+
+    #[test]
+    fn test_push_frames() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        let translation = Transform::new_translation(&na::Vector3::x());
+        viewer.push_frames(&[(translation, "a".to_string()), (translation, "b".to_string())]).unwrap();
+
+        let frames = viewer.frames.read();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames["a"].transform, translation);
+        assert_eq!(frames["b"].transform, translation);
+    }
+
+    #[test]
+    fn test_push_invalid_frames() {
+        let viewer = Viewer::new();
+        let mut nan_translation = Transform::identity();
+        nan_translation[(0, 3)] = f32::NAN;
+        viewer.push_frame(Transform::identity(), "test_frame").unwrap();
+        assert!(matches!(viewer.push_frame(nan_translation, "test_frame"), Err(ViewerError::InvalidTransform(_))));
+        assert_eq!(viewer.frames.read()["test_frame"].transform, Transform::identity());
+        viewer.remove_frame("test_frame");
+
+        let invalid = Transform::from_element(f32::NAN);
+        let batch = [(Transform::identity(), "a".to_string()), (invalid, "b".to_string())];
+        assert!(matches!(viewer.push_frames(&batch), Err(ViewerError::InvalidTransform(name)) if name == "b"));
+        assert!(viewer.push_frame_scaled(invalid, "c", 2.0).is_err());
+        assert!(viewer.push_frame_colored(invalid, "c", [[1.0; 4]; 3]).is_err());
+        assert!(viewer.push_frame_interpolated("c", invalid, Duration::from_secs(1)).is_err());
+        // Not even the valid frame of the batch was pushed
+        assert!(viewer.frame_names().is_empty());
+    }
+
+    #[test]
+    fn test_set_frame_visible() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        assert!(viewer.set_frame_visible("a", false));
+        assert!(!viewer.set_frame_visible("missing", false));
+
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        assert!(!viewer.frames.read()["a"].visible);
+    }
+
+    #[test]
+    fn test_push_frame_ttl() {
+        let viewer = Viewer::new();
+        viewer.push_frame_ttl(Transform::identity(), "expired", Duration::ZERO).unwrap();
+        viewer.push_frame_ttl(Transform::identity(), "alive", Duration::from_secs(60)).unwrap();
+        viewer.push_frame_ttl(Transform::identity(), "permanent", Duration::ZERO).unwrap();
+        viewer.push_frame(Transform::identity(), "permanent").unwrap();
+
+        viewer.update(0.0, None);
+        let mut names = viewer.frame_names();
+        names.sort();
+        assert_eq!(names, vec!["alive".to_string(), "permanent".to_string()]);
+    }
+
+    #[test]
+    fn test_pick_frame_not_running() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        let (x, y) = viewer.project_to_screen("a").unwrap();
+        // Nothing draws the frames to pick from
+        assert_eq!(viewer.pick_frame(x, y), None);
+    }
+
+    #[test]
+    fn test_pick_frame_stopped_while_queued() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        let (x, y) = viewer.project_to_screen("a").unwrap();
+        // Started, but nothing draws the frames to pick from
+        *viewer.running.write() = true;
+        let requester = viewer.share();
+        let pick = thread::spawn(move || requester.pick_frame(x, y));
+        thread::sleep(Duration::from_millis(50));
+        viewer.stop();
+        assert_eq!(pick.join().unwrap(), None);
+
+        // The render loop drops the request when it exits
+        let (reply, response) = mpsc::channel();
+        viewer.commands.send(Command::PickFrame(Pick { x, y, reply })).unwrap();
+        viewer.reject_draw_requests();
+        assert!(response.recv().is_err());
+    }
+
+    #[test]
+    fn test_screenshot_stopped_while_queued() {
+        let viewer = Viewer::new();
+        // Started, but nothing renders the requested image
+        *viewer.running.write() = true;
+        let requester = viewer.share();
+        let screenshot = thread::spawn(move || requester.screenshot("unused.png"));
+        thread::sleep(Duration::from_millis(50));
+        viewer.stop();
+        assert!(matches!(screenshot.join().unwrap(), Err(ViewerError::NotRunning)));
+    }
+
+    #[test]
+    fn test_reject_draw_requests() {
+        let viewer = Viewer::new();
+        let (reply, response) = mpsc::channel();
+        let path = "unused.png".into();
+        viewer.commands.send(Command::Screenshot(Screenshot { path, reply })).unwrap();
+        viewer.reject_draw_requests();
+        assert!(response.recv().is_err());
+    }
+
+    #[test]
+    fn test_fit_view() {
+        let viewer = Viewer::new();
+        assert!(!viewer.fit_view());
+
+        viewer.push_frame(Transform::new_translation(&na::Vector3::new(2.0, 0.0, 0.0)), "a").unwrap();
+        viewer.push_points("cloud", &[[4.0, 0.0, 0.0]], [1.0, 0.0, 0.0, 1.0]);
+        assert!(viewer.fit_view());
+        let (position, target, _) = viewer.camera_pose();
+        assert!((target[0] - 3.0).abs() < 1e-5);
+        let distance = (na::Vector3::from(position) - na::Vector3::from(target)).norm();
+        assert!(distance > 1.0);
+    }
+
+    #[test]
+    fn test_set_standard_view() {
+        let viewer = Viewer::new();
+        let distance = |(position, target, _): ([f32; 3], [f32; 3], [f32; 3])| {
+            (na::Vector3::from(position) - na::Vector3::from(target)).norm()
+        };
+        let before = distance(viewer.camera_pose());
+
+        viewer.set_standard_view(StandardView::Front);
+        let (position, target, _) = viewer.camera_pose();
+        let offset = (na::Vector3::from(position) - na::Vector3::from(target)).normalize();
+        assert!((offset - -na::Vector3::y()).norm() < 1e-5);
+        assert!((distance(viewer.camera_pose()) - before).abs() < 1e-5);
+
+        viewer.set_standard_view(StandardView::Top);
+        let (position, target, _) = viewer.camera_pose();
+        assert!(position[2] - target[2] > before * 0.99);
+    }
+
+    #[test]
+    fn test_set_2d_mode() {
+        let viewer = Viewer::new();
+        let (position, target, up) = viewer.camera_pose();
+
+        viewer.set_2d_mode(true);
+        let (top_position, _, top_up) = viewer.camera_pose();
+        assert_eq!([top_position[0], top_position[1]], [target[0], target[1]]);
+        assert_eq!(top_up, [0.0, 1.0, 0.0]);
+        assert!(matches!(viewer.projection_mode(), ProjectionMode::Orthographic { .. }));
+
+        viewer.set_2d_mode(false);
+        assert_eq!(viewer.camera_pose(), (position, target, up));
+        assert_eq!(viewer.projection_mode(), ProjectionMode::Perspective);
+    }
+
+    #[test]
+    fn test_2d_mode_from_config() {
+        let viewer = Viewer::from_config(ViewerConfig { mode_2d: true, ..ViewerConfig::default() });
+        assert!(matches!(viewer.projection_mode(), ProjectionMode::Orthographic { .. }));
+
+        viewer.set_2d_mode(false);
+        assert_eq!(viewer.projection_mode(), ProjectionMode::Perspective);
+    }
+
+    #[test]
+    fn test_2d_mode_stays_top_down() {
+        let viewer = Viewer::new();
+        viewer.set_2d_mode(true);
+        let looks_down = || {
+            let (position, target, _) = viewer.camera_pose();
+            let direction = (na::Vector3::from(target) - na::Vector3::from(position)).normalize();
+            (direction - -na::Vector3::z()).norm() < 1e-5
+                && matches!(viewer.projection_mode(), ProjectionMode::Orthographic { .. })
+        };
+
+        viewer.reset_camera();
+        assert!(looks_down());
+        viewer.set_standard_view(StandardView::Front);
+        assert!(looks_down());
+        viewer.set_projection_mode(ProjectionMode::Perspective);
+        assert!(looks_down());
+        assert!(viewer.set_camera_pose([1.0, 2.0, 3.0], [0.0, 0.0, 0.0], [0.0, 0.0, 1.0]));
+        assert!(looks_down());
+    }
+
+    #[test]
+    fn test_replace_frames() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        viewer.push_frame(Transform::identity(), "b").unwrap();
+        viewer.set_frame_visible("b", false);
+
+        let frames = [(Transform::identity(), "b".to_string()), (Transform::identity(), "c".to_string())];
+        viewer.replace_frames(&frames).unwrap();
+        let mut names = viewer.frame_names();
+        names.sort();
+        assert_eq!(names, vec!["b".to_string(), "c".to_string()]);
+        assert!(!viewer.frames.read()["b"].visible);
+
+        let invalid = [(Transform::from_element(f32::NAN), "d".to_string())];
+        assert!(viewer.replace_frames(&invalid).is_err());
+        assert_eq!(viewer.frame_names().len(), 2);
+    }
+
+    #[test]
+    fn test_clear_all() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        viewer.push_points("cloud", &[[0.0, 0.0, 0.0]], [1.0, 0.0, 0.0, 1.0]);
+        viewer.push_lines("segments", &[([0.0, 0.0, 0.0], [1.0, 0.0, 0.0])], [1.0, 0.0, 0.0, 1.0]);
+
+        viewer.clear_frames();
+        assert!(viewer.frame_names().is_empty());
+        assert_eq!(viewer.points.read().len(), 1);
+
+        viewer.clear_all();
+        assert!(viewer.points.read().is_empty());
+        assert!(viewer.lines.read().is_empty());
+    }
+
+    #[test]
+    fn test_wait_without_start() {
+        let viewer = Viewer::new();
+        viewer.wait();
+        assert!(!viewer.is_running());
+    }
+
+    #[test]
+    fn test_close_signal() {
+        let viewer = Viewer::new();
+        let signal = viewer.close_signal();
+        assert!(signal.try_recv().is_err());
+
+        viewer.notify_closed();
+        viewer.notify_closed();
+        assert_eq!(signal.try_recv(), Ok(()));
+        assert_eq!(signal.try_recv(), Err(mpsc::TryRecvError::Disconnected));
+    }
+
+    #[test]
+    fn test_set_frame_always_on_top() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        assert!(viewer.set_frame_always_on_top("a", true));
+        assert!(!viewer.set_frame_always_on_top("missing", true));
+
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        assert!(viewer.frames.read()["a"].always_on_top);
+    }
+
+    #[test]
+    fn test_set_frame_ghost() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        assert!(viewer.set_frame_ghost("a", true));
+        assert!(!viewer.set_frame_ghost("missing", true));
+        assert!(viewer.frames.read()["a"].ghost);
+
+        // A new pose makes the frame live again
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        assert!(!viewer.frames.read()["a"].ghost);
+    }
+
+    #[test]
+    fn test_set_frame_alpha() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        viewer.style_frame("a", FrameStyle { axis_length: 0.5, ..FrameStyle::default() });
+        assert!(viewer.set_frame_alpha("a", 2.0));
+        assert!(!viewer.set_frame_alpha("missing", 0.5));
+
+        let style = viewer.frames.read()["a"].style;
+        assert_eq!(style.alpha, 1.0);
+        assert_eq!(style.axis_length, 0.5);
+    }
+
+    #[test]
+    fn test_set_axis_length() {
+        let viewer = Viewer::new();
+        assert!(viewer.set_axis_length(0.5));
+        assert_eq!(viewer.config().axis_length, 0.5);
+        assert!(!viewer.set_axis_length(0.0));
+        assert_eq!(viewer.config().axis_length, 0.5);
+    }
+
+    #[test]
+    fn test_set_clip_planes() {
+        let viewer = Viewer::new();
+        assert!(viewer.set_clip_planes(1.0, 5000.0));
+        assert_eq!(viewer.clip_planes(), (1.0, 5000.0));
+        assert!(!viewer.set_clip_planes(0.0, 10.0));
+        assert!(!viewer.set_clip_planes(10.0, 1.0));
+        assert_eq!(viewer.clip_planes(), (1.0, 5000.0));
+    }
+
+    #[test]
+    fn test_set_zoom_mode() {
+        let viewer = Viewer::new();
+        viewer.set_zoom_mode(ZoomMode::Fov);
+        let position = viewer.camera.read().position();
+        let fov = viewer.fov();
+
+        viewer.camera.write().zoom(1.0);
+        assert_eq!(viewer.camera.read().position(), position);
+        assert!(viewer.fov() < fov);
+
+        viewer.set_fov(10.0);
+        assert!(viewer.fov() < std::f32::consts::PI);
+    }
+
+    #[test]
+    fn test_frame_names() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        viewer.push_frame(Transform::identity(), "b").unwrap();
+        viewer.remove_frame("a");
+        assert_eq!(viewer.frame_names(), vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn test_rename_frame() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        viewer.push_frame(Transform::identity(), "b").unwrap();
+
+        assert!(viewer.rename_frame("a", "c"));
+        assert!(!viewer.rename_frame("a", "d"));
+        assert!(!viewer.rename_frame("c", "b"));
+        assert!(viewer.frames.read().contains_key("c"));
+        assert!(!viewer.frames.read().contains_key("a"));
+    }
+
+    #[test]
+    fn test_invalid_grid() {
+        let viewer = Viewer::new();
+        assert!(!viewer.set_grid(1.0, 1e-9));
+        assert!(!viewer.set_grid(f32::INFINITY, 0.1));
+        assert!(!viewer.set_grid(1.0, 0.0));
+        assert!(!viewer.set_grid(-1.0, 0.1));
+        assert_eq!(viewer.config().grid, GridConfig::default());
+
+        let config = GridConfig { step: f32::NAN, ..GridConfig::default() };
+        assert!(!viewer.add_grid("a", Transform::identity(), config));
+        assert!(!viewer.add_grid("a", Transform::from_element(f32::NAN), GridConfig::default()));
+        assert!(viewer.add_grid("a", Transform::identity(), GridConfig::default()));
+        assert_eq!(viewer.grids.read().len(), 1);
+    }
+
+    #[test]
+    fn test_grid_ticks() {
+        let grid = GridConfig::default();
+        let ticks: Vec<String> = grid_ticks(&grid, [0.0, 0.0, 1.0]).into_iter().map(|(_, text)| text).collect();
+        assert_eq!(ticks, ["0.5", "1", "0.5", "1"]);
+        assert_eq!(grid_ticks(&grid, [0.0, 0.0, 1.0])[2].0, na::Point3::new(0.0, 0.5, 0.0));
+    }
+
+    #[test]
+    fn test_axis_angle() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        let rotation = na::Matrix4::from_axis_angle(&na::Vector3::z_axis(), std::f32::consts::FRAC_PI_2);
+        viewer.push_frame(rotation, "b").unwrap();
+
+        let angle = viewer.axis_angle("a", Axis::X, "b", Axis::X).unwrap();
+        assert!((angle - std::f32::consts::FRAC_PI_2).abs() < 1e-5);
+        let angle = viewer.axis_angle("a", Axis::Y, "b", Axis::X).unwrap();
+        assert!(angle.abs() < 1e-5);
+        assert!(viewer.axis_angle("a", Axis::X, "missing", Axis::X).is_none());
+    }
+
+    #[test]
+    fn test_project_to_screen() {
+        let viewer = Viewer::new();
+        let target = viewer.config().camera.target;
+        viewer.push_frame(na::Matrix4::new_translation(&target.into()), "target").unwrap();
+        let behind = viewer.config().camera.position.map(|x| x * 2.0);
+        viewer.push_frame(na::Matrix4::new_translation(&behind.into()), "behind").unwrap();
+
+        let config = viewer.config();
+        let (x, y) = viewer.project_to_screen("target").unwrap();
+        assert!((x - config.width as f32 / 2.0).abs() < 1e-3);
+        assert!((y - config.height as f32 / 2.0).abs() < 1e-3);
+        assert!(viewer.project_to_screen("behind").is_none());
+        assert!(viewer.project_to_screen("missing").is_none());
+    }
+
+    #[test]
+    fn test_handle_sends_commands() {
+        let viewer = Viewer::new();
+        let handle = viewer.handle();
+        handle.push_frame(Transform::identity(), "a").unwrap();
+        handle.clone().clear_frames();
+
+        let commands: Vec<Command> = viewer.command_receiver.lock().try_iter().collect();
+        assert!(matches!(commands.as_slice(), [Command::PushFrame(name, _), Command::ClearFrames] if name == "a"));
+    }
+
+    #[test]
+    fn test_covariance_transform() {
+        let center = na::Vector3::new(1.0, 2.0, 3.0);
+        let covariance = na::Matrix3::from_diagonal(&na::Vector3::new(4.0, 1.0, 0.25));
+        let transform = covariance_transform(center, covariance);
+
+        // The unit sphere's X, Y and Z extremities land on the 1-sigma bounds
+        let expected = [2.0, 1.0, 0.5];
+        for (axis, radius) in expected.iter().enumerate() {
+            let point = transform.transform_point(&na::Point3::from(na::Vector3::ith(axis, 1.0)));
+            assert!(((point.coords - center).norm() - radius).abs() < 1e-5);
+        }
+        assert_eq!(transform.fixed_view::<3, 1>(0, 3), center);
+    }
+
+    #[test]
+    fn test_style_kept_across_pushes() {
+        let viewer = Viewer::new();
+        let style = FrameStyle {
+            axis_length: 0.5,
+            ..FrameStyle::default()
+        };
+        assert!(!viewer.style_frame("a", style));
+
+        viewer.push_frame(Transform::identity(), "a").unwrap();
+        assert!(viewer.style_frame("a", style));
+        viewer.push_frame(Transform::new_translation(&na::Vector3::x()), "a").unwrap();
+        assert_eq!(viewer.frames.read()["a"].style, style);
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let from = Transform::identity();
+        let to = na::Matrix4::new_translation(&na::Vector3::new(2.0, 0.0, 0.0))
+            * na::Matrix4::from_axis_angle(&na::Vector3::z_axis(), std::f32::consts::FRAC_PI_2);
+
+        let halfway = interpolate(&from, &to, 0.5);
+        let expected = na::Matrix4::new_translation(&na::Vector3::new(1.0, 0.0, 0.0))
+            * na::Matrix4::from_axis_angle(&na::Vector3::z_axis(), std::f32::consts::FRAC_PI_4);
+        assert!((halfway - expected).abs().max() < 1e-5);
+        assert!((interpolate(&from, &to, 1.0) - to).abs().max() < 1e-5);
+    }
+
+    #[test]
+    fn test_reset_appearance() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::new_translation(&na::Vector3::x()), "a").unwrap();
+        viewer.style_frame("a", FrameStyle { alpha: 0.5, ..FrameStyle::default() });
+        viewer.set_color_by_name(true);
+        viewer.set_scale_bar(true);
+        viewer.set_gnomon(true);
+        assert!(viewer.set_grid(20.0, 1.0));
+        viewer.set_background_color(0.0, 0.0, 0.0, 1.0);
+
+        viewer.reset_appearance();
+        assert_eq!(viewer.config(), ViewerConfig::default());
+        let frames = viewer.frames.read();
+        assert_eq!(frames["a"].style, FrameStyle::default());
+        assert_eq!(frames["a"].transform, Transform::new_translation(&na::Vector3::x()));
+    }
+
+    #[test]
+    fn test_reset_camera() {
+        let viewer = Viewer::builder().camera([2.0, 1.0, 1.0], [0.0, 0.0, 0.5]).build();
+        let view = viewer.view_matrix();
+        viewer.camera.write().orbit(0.3, 0.2);
+        viewer.camera.write().zoom(1.0);
+        assert_ne!(viewer.view_matrix(), view);
+
+        viewer.reset_camera();
+        assert_eq!(viewer.view_matrix(), view);
+    }
+
+    #[test]
+    fn test_zoom_limits() {
+        let viewer = Viewer::builder().camera([1.0, 0.0, 0.0], [0.0, 0.0, 0.0]).build();
+        assert!(viewer.set_zoom_limits(0.5, 2.0));
+        assert!(!viewer.set_zoom_limits(0.0, 2.0));
+        assert!(!viewer.set_zoom_limits(3.0, 2.0));
+        assert!(!viewer.set_zoom_limits(f32::NAN, 2.0));
+        assert!(!viewer.set_zoom_limits(0.5, f32::INFINITY));
+        // Distance from the camera to its target, the world origin
+        let distance = || viewer.view_matrix().transform_point(&na::Point3::origin()).coords.norm();
+
+        viewer.camera.write().zoom(100.0);
+        assert!((distance() - 0.5).abs() < 1e-5);
+        viewer.camera.write().zoom(-100.0);
+        assert!((distance() - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_set_world_up() {
+        let viewer = Viewer::new();
+        assert!(viewer.set_world_up([0.0, 1.0, 0.0]));
+        assert!(!viewer.set_world_up([0.0, 0.0, 0.0]));
+        assert_eq!(viewer.config().world_up, [0.0, 1.0, 0.0]);
+
+        viewer.reset_camera();
+        assert_eq!(viewer.camera_pose().2, [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_camera_pose() {
+        let viewer = Viewer::new();
+        let pose = ([1.0, 2.0, 3.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]);
+        assert!(viewer.set_camera_pose(pose.0, pose.1, pose.2));
+        assert_eq!(viewer.camera_pose(), pose);
+
+        // Looking straight along the up direction is rejected
+        assert!(!viewer.set_camera_pose([0.0, 0.0, 2.0], [0.0, 0.0, 0.0], [0.0, 0.0, 1.0]));
+        assert_eq!(viewer.camera_pose(), pose);
+    }
+
+    #[test]
+    fn test_focus_on_frame() {
+        let viewer = Viewer::new();
+        let translation = na::Vector3::new(1.0, 2.0, 3.0);
+        viewer.push_frame(Transform::new_translation(&translation), "a").unwrap();
+        let view = viewer.view_matrix();
+
+        assert!(viewer.focus_on_frame("a"));
+        assert!(!viewer.focus_on_frame("b"));
+        assert_eq!(viewer.camera.read().target(), na::Point3::from(translation));
+        assert!((viewer.view_matrix() - view * Transform::new_translation(&-translation)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn test_orthographic_zoom() {
+        let viewer = Viewer::new();
+        let view = viewer.view_matrix();
+        viewer.set_projection_mode(ProjectionMode::Orthographic { height: 2.0 });
+        assert_eq!(viewer.projection_matrix()[(1, 1)], 1.0);
+
+        // Zooming in narrows the visible height and leaves the camera in place
+        viewer.camera.write().zoom(1.0);
+        assert!(viewer.projection_matrix()[(1, 1)] > 1.0);
+        assert_eq!(viewer.view_matrix(), view);
+    }
+
+    #[test]
+    fn test_builder() {
+        let viewer = Viewer::builder().vsync(true).build();
+        assert!(viewer.config().vsync);
+    }
+
+    #[test]
+    fn test_invalid_camera_falls_back() {
+        let viewer = Viewer::builder().camera([0.0, 0.0, 2.0], [0.0, 0.0, 0.0]).build();
+        assert_eq!(viewer.config().camera, CameraConfig::default());
+
+        let viewer = Viewer::builder().camera([2.0, 2.0, 2.0], [0.0, 0.0, 0.0]).build();
+        assert_eq!(viewer.config().camera.position, [2.0, 2.0, 2.0]);
+
+        let viewer = Viewer::builder().projection(ProjectionMode::Orthographic { height: 0.0 }).build();
+        assert_eq!(viewer.config().camera, CameraConfig::default());
+    }
+
+    #[test]
+    fn test_projection_from_config() {
+        let orthographic = ProjectionMode::Orthographic { height: 3.0 };
+        let viewer = Viewer::builder().projection(orthographic).build();
+        assert_eq!(viewer.projection_mode(), orthographic);
+        assert_eq!(Viewer::new().projection_mode(), ProjectionMode::Perspective);
+    }
+
+    #[test]
+    fn test_msaa_samples_rounded_down() {
+        assert_eq!(Viewer::builder().msaa_samples(3).build().config().msaa_samples, 2);
+        assert_eq!(Viewer::builder().msaa_samples(8).build().config().msaa_samples, 8);
+        assert_eq!(Viewer::builder().msaa_samples(0).build().config().msaa_samples, 0);
+    }
+
+    #[test]
+    fn test_push_frame() {
+        let viewer = Viewer::new();
+        let transform = Transform::identity();
+        viewer.push_frame(transform, "test_frame").unwrap();
+        assert_eq!(viewer.frames.read().len(), 1);
+    }
+
+    #[test]
+    fn test_frame_age() {
+        let viewer = Viewer::new();
+        viewer.push_frame(Transform::identity(), "test_frame").unwrap();
+        assert!(viewer.frame_age("test_frame").is_some());
+        assert!(viewer.frame_age("missing").is_none());
+    }
+
+    #[test]
+    fn test_get_frame() {
+        let viewer = Viewer::new();
+        let transform = Transform::new_translation(&na::Vector3::new(1.0, 2.0, 3.0));
+        viewer.push_frame(transform, "test_frame").unwrap();
+        assert_eq!(viewer.get_frame("test_frame"), Some(transform));
+        assert_eq!(viewer.get_frame("missing"), None);
     }
 }
 
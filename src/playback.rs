@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use crate::Transform;
+
+/// A set of named frames recorded at a given timestamp (in seconds).
+pub type Sample = (f64, HashMap<String, Transform>);
+
+/// Plays back a recorded sequence of frame sets.
+///
+/// The render loop advances the playback clock every tick and applies the
+/// frame set of the latest sample whose timestamp is not after the clock.
+pub struct Playback {
+    samples: Vec<Sample>,
+    names: Vec<String>,
+    time: f64,
+    speed: f64,
+    playing: bool,
+    current: Option<usize>,
+    dirty: bool,
+}
+
+impl Playback {
+    pub fn new() -> Self {
+        Playback {
+            samples: Vec::new(),
+            names: Vec::new(),
+            time: 0.0,
+            speed: 1.0,
+            playing: false,
+            current: None,
+            dirty: false,
+        }
+    }
+
+    /// Replace the recorded sequence and rewind to its first sample.
+    pub fn load(&mut self, mut samples: Vec<Sample>) {
+        samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut names: Vec<String> = samples.iter().flat_map(|s| s.1.keys().cloned()).collect();
+        names.sort();
+        names.dedup();
+
+        self.time = samples.first().map_or(0.0, |s| s.0);
+        self.names = names;
+        self.samples = samples;
+        self.playing = false;
+        self.current = None;
+        self.dirty = true;
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn seek(&mut self, time: f64) {
+        self.time = self.clamp(time);
+        self.dirty = true;
+    }
+
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed;
+    }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Names of every frame that appears anywhere in the sequence.
+    pub fn frame_names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Frame set of the sample currently being displayed.
+    pub fn current_frames(&self) -> Option<&HashMap<String, Transform>> {
+        self.current.map(|index| &self.samples[index].1)
+    }
+
+    /// Advance the clock by `dt` seconds of wall time.
+    ///
+    /// Returns true if the displayed frame set changed since the last call.
+    pub fn advance(&mut self, dt: f64) -> bool {
+        if self.samples.is_empty() {
+            return false;
+        }
+
+        if self.playing {
+            self.time = self.clamp(self.time + dt * self.speed);
+            let (start, end) = self.bounds();
+            if (self.speed > 0.0 && self.time >= end) || (self.speed < 0.0 && self.time <= start) {
+                self.playing = false;
+            }
+        }
+
+        let index = self.samples.partition_point(|s| s.0 <= self.time).saturating_sub(1);
+        let changed = self.current != Some(index) || self.dirty;
+        self.current = Some(index);
+        self.dirty = false;
+        changed
+    }
+
+    fn bounds(&self) -> (f64, f64) {
+        match (self.samples.first(), self.samples.last()) {
+            (Some(first), Some(last)) => (first.0, last.0),
+            _ => (0.0, 0.0),
+        }
+    }
+
+    fn clamp(&self, time: f64) -> f64 {
+        let (start, end) = self.bounds();
+        time.clamp(start, end)
+    }
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra as na;
+
+    fn sample(time: f64, x: f32) -> Sample {
+        let mut frames = HashMap::new();
+        frames.insert("a".to_string(), Transform::new_translation(&na::Vector3::new(x, 0.0, 0.0)));
+        (time, frames)
+    }
+
+    #[test]
+    fn test_advance_and_seek() {
+        let mut playback = Playback::new();
+        playback.load(vec![sample(1.0, 1.0), sample(0.0, 0.0), sample(2.0, 2.0)]);
+
+        assert!(playback.advance(0.0));
+        assert_eq!(playback.current_frames().unwrap()["a"][(0, 3)], 0.0);
+        assert!(!playback.advance(0.0));
+
+        playback.play();
+        assert!(playback.advance(1.5));
+        assert_eq!(playback.current_frames().unwrap()["a"][(0, 3)], 1.0);
+
+        playback.advance(10.0);
+        assert!(!playback.is_playing());
+        assert_eq!(playback.time(), 2.0);
+
+        playback.seek(0.5);
+        assert!(playback.advance(0.0));
+        assert_eq!(playback.current_frames().unwrap()["a"][(0, 3)], 0.0);
+    }
+}
@@ -1,9 +1,23 @@
 use pyo3::prelude::*;
-use numpy::{PyArray2, PyReadonlyArray2};
+use numpy::{PyArray2, PyReadonlyArray2, PyReadonlyArray3};
 use nalgebra as na;
+use std::collections::HashMap;
+use std::time::Duration;
 
 use crate::Viewer as RustViewer;
-use crate::Transform;
+use crate::is_valid_msaa_samples;
+use crate::{Axis, CameraConfig, FrameStyle, GridConfig, GridPlanes, GridStyle, Handedness, MouseButton, ProjectionMode, StandardView, Transform, VirtualKeyCode, ZoomMode};
+
+const MESH_COLOR: [f32; 4] = [0.7, 0.7, 0.7, 1.0]; // Light gray
+
+/// A world plane the grid can be drawn in, for Viewer.set_grid_planes.
+#[pyclass(name = "GridPlane")]
+#[derive(Clone, Copy)]
+pub enum PyGridPlane {
+    XY,
+    XZ,
+    YZ,
+}
 
 #[pyclass(name = "Viewer")]
 /// A real-time 6D frames viewer with OpenGL rendering.
@@ -16,7 +30,10 @@ use crate::Transform;
 ///     - Real-time visualization of multiple coordinate frames
 ///     - Metric units (meters)
 ///     - Interactive camera controls (orbit, pan, zoom)
-///     - Grid visualization in XY, XZ, and YZ planes
+///     - Grid visualization on the ground plane
+///
+/// Used as a context manager (`with Viewer() as viewer:`), the viewer starts
+/// when entering the block and closes its window when leaving it.
 struct PyViewer {
     viewer: RustViewer,
 }
@@ -24,14 +41,78 @@ struct PyViewer {
 #[pymethods]
 impl PyViewer {
     #[new]
+    #[pyo3(signature = (
+        vsync = false,
+        camera_position = None,
+        camera_target = None,
+        always_on_top = false,
+        decorations = true,
+        close_on_escape = true,
+        title = "Frames Viewer",
+        width = 800,
+        height = 600,
+        headless = false,
+        msaa_samples = 4,
+    ))]
     /// Initialize a new frames viewer.
     ///
+    /// Args:
+    ///     vsync (bool): Synchronize rendering with the display refresh rate.
+    ///         Removes tearing and lowers CPU usage at the cost of latency.
+    ///     camera_position (Optional[list[float]]): Initial [x, y, z] camera position in meters
+    ///     camera_target (Optional[list[float]]): Initial [x, y, z] point the camera looks at
+    ///     always_on_top (bool): Keep the window above all other windows
+    ///     decorations (bool): Show the window title bar and borders
+    ///     close_on_escape (bool): Close the window when Escape is pressed
+    ///     title (str): Window title
+    ///     width (int): Initial window width in logical pixels
+    ///     height (int): Initial window height in logical pixels
+    ///     headless (bool): Render offscreen at width x height pixels instead of
+    ///         opening a window, e.g. to take screenshots on a CI server
+    ///     msaa_samples (int): Samples per pixel used to smooth lines, a power of two,
+    ///         or 0 to disable anti-aliasing
+    ///
     /// Returns:
     ///     Viewer: A new instance of the frames viewer.
-    fn new() -> Self {
-        PyViewer {
-            viewer: RustViewer::new(),
+    ///
+    /// Raises:
+    ///     ValueError: If msaa_samples is neither 0 nor a power of two
+    #[allow(clippy::too_many_arguments)] // Mirrors the Python keyword arguments
+    fn new(
+        vsync: bool,
+        camera_position: Option<[f32; 3]>,
+        camera_target: Option<[f32; 3]>,
+        always_on_top: bool,
+        decorations: bool,
+        close_on_escape: bool,
+        title: &str,
+        width: u32,
+        height: u32,
+        headless: bool,
+        msaa_samples: u16,
+    ) -> PyResult<Self> {
+        if !is_valid_msaa_samples(msaa_samples) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "msaa_samples must be 0 or a power of two",
+            ));
         }
+        let default_camera = CameraConfig::default();
+        let builder = RustViewer::builder()
+            .vsync(vsync)
+            .camera(
+                camera_position.unwrap_or(default_camera.position),
+                camera_target.unwrap_or(default_camera.target),
+            )
+            .always_on_top(always_on_top)
+            .decorations(decorations)
+            .close_key(close_on_escape.then_some(VirtualKeyCode::Escape))
+            .title(title)
+            .size(width, height)
+            .headless(headless)
+            .msaa_samples(msaa_samples);
+        Ok(PyViewer {
+            viewer: builder.build(),
+        })
     }
 
     /// Start the viewer in a separate thread.
@@ -60,44 +141,1627 @@ impl PyViewer {
     ///     None
     ///
     /// Raises:
-    ///     ValueError: If transform is not a 4x4 matrix
+    ///     ValueError: If transform is not a finite 4x4 matrix
     fn push_frame(&self, transform: PyReadonlyArray2<f32>, name: &str) -> PyResult<()> {
-        let array = transform.as_array();
-        if array.shape() != [4, 4] {
+        self.viewer
+            .push_frame(to_transform(&transform)?, name)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Push a frame given in double precision, e.g. in UTM coordinates far from
+    /// the origin, where float32 would make frames jitter.
+    ///
+    /// The frame is stored relative to the world origin, set to the translation
+    /// of the first frame pushed this way unless set with `set_world_origin`.
+    /// Other geometry must be given relative to that origin.
+    ///
+    /// Args:
+    ///     transform (numpy.ndarray): A 4x4 homogeneous transformation matrix (float64)
+    ///     name (str): Unique identifier for the frame
+    ///
+    /// Raises:
+    ///     ValueError: If transform is not a finite 4x4 matrix
+    fn push_frame_f64(&self, transform: PyReadonlyArray2<f64>, name: &str) -> PyResult<()> {
+        self.viewer
+            .push_frame_f64(to_transform(&transform)?, name)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Set the point that frames pushed with `push_frame_f64` are stored
+    /// relative to. Frames already pushed are not moved.
+    ///
+    /// Args:
+    ///     origin (tuple[float, float, float]): World coordinates of the origin
+    fn set_world_origin(&self, origin: [f64; 3]) {
+        self.viewer.set_world_origin(origin);
+    }
+
+    /// Push a frame that disappears unless pushed again within `ttl` seconds.
+    ///
+    /// Pushing the frame with `push_frame` makes it permanent again.
+    ///
+    /// Args:
+    ///     transform (numpy.ndarray): A 4x4 homogeneous transformation matrix (float32)
+    ///     name (str): Unique identifier for the frame
+    ///     ttl (float): Time to live in seconds
+    ///
+    /// Raises:
+    ///     ValueError: If transform is not a finite 4x4 matrix or ttl is negative
+    fn push_frame_ttl(&self, transform: PyReadonlyArray2<f32>, name: &str, ttl: f32) -> PyResult<()> {
+        let ttl = Duration::try_from_secs_f32(ttl).map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>("Time to live must be a non-negative number of seconds")
+        })?;
+        self.viewer
+            .push_frame_ttl(to_transform(&transform)?, name, ttl)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Push a frame from a rotation matrix and a translation.
+    ///
+    /// Args:
+    ///     rotation (numpy.ndarray): A 3x3 rotation matrix (float32)
+    ///     translation (list[float]): [x, y, z] position in meters
+    ///     name (str): Unique identifier for the frame
+    ///
+    /// Raises:
+    ///     ValueError: If rotation is not a 3x3 matrix or the pose is not finite
+    fn push_frame_rt(&self, rotation: PyReadonlyArray2<f32>, translation: [f32; 3], name: &str) -> PyResult<()> {
+        let array = rotation.as_array();
+        if array.shape() != [3, 3] {
             return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
-                "Transform must be a 4x4 matrix",
+                "Rotation must be a 3x3 matrix",
             ));
         }
+        let rotation = na::Matrix3::from_fn(|i, j| array[[i, j]]);
+        self.viewer
+            .push_frame_rt(rotation, translation.into(), name)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
 
-        let mut matrix = na::Matrix4::identity();
-        for i in 0..4 {
-            for j in 0..4 {
-                matrix[(i, j)] = array[[i, j]];
-            }
+    /// Push a frame from a position and an orientation quaternion.
+    ///
+    /// Args:
+    ///     position (list[float]): [x, y, z] position in meters
+    ///     quaternion (list[float]): [x, y, z, w] orientation, normalized before use
+    ///     name (str): Unique identifier for the frame
+    ///
+    /// Raises:
+    ///     ValueError: If the quaternion is zero or the pose is not finite
+    fn push_frame_quat(&self, position: [f32; 3], quaternion: [f32; 4], name: &str) -> PyResult<()> {
+        let [x, y, z, w] = quaternion;
+        let rotation = na::UnitQuaternion::try_new(na::Quaternion::new(w, x, y, z), f32::EPSILON).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>("Quaternion must not be zero")
+        })?;
+        let transform = na::Isometry3::from_parts(na::Translation3::from(position), rotation).to_homogeneous();
+        self.viewer
+            .push_frame(transform, name)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Push many frames at once.
+    ///
+    /// The whole batch is applied atomically: the viewer never draws a
+    /// partially updated batch.
+    ///
+    /// Args:
+    ///     frames (list[tuple[numpy.ndarray, str]]): (transform, name) pairs, each
+    ///         transform a 4x4 homogeneous transformation matrix (float32)
+    ///
+    /// Raises:
+    ///     ValueError: If any transform is not a finite 4x4 matrix, in which case no frame is pushed
+    fn push_frames(&self, frames: Vec<(PyReadonlyArray2<f32>, String)>) -> PyResult<()> {
+        let frames = frames
+            .iter()
+            .map(|(transform, name)| Ok((to_transform(transform)?, name.clone())))
+            .collect::<PyResult<Vec<_>>>()?;
+        self.viewer
+            .push_frames(&frames)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Make the given frames the only frames in the viewer, in one step.
+    ///
+    /// Unlike clear_frames followed by push_frame calls, the viewer never draws
+    /// an empty or half updated scene. Frames that stay keep their style.
+    ///
+    /// Args:
+    ///     frames (list[tuple[numpy.ndarray, str]]): (transform, name) pairs, each
+    ///         transform a 4x4 homogeneous transformation matrix (float32)
+    ///
+    /// Raises:
+    ///     ValueError: If any transform is not a finite 4x4 matrix, in which case nothing changes
+    fn replace_frames(&self, frames: Vec<(PyReadonlyArray2<f32>, String)>) -> PyResult<()> {
+        let frames = frames
+            .iter()
+            .map(|(transform, name)| Ok((to_transform(transform)?, name.clone())))
+            .collect::<PyResult<Vec<_>>>()?;
+        self.viewer
+            .replace_frames(&frames)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Push a frame with axes scaled relative to the default 10 centimeters.
+    ///
+    /// The scale is kept when the frame is later updated with push_frame.
+    ///
+    /// Args:
+    ///     transform (numpy.ndarray): A 4x4 homogeneous transformation matrix (float32)
+    ///     name (str): Unique identifier for the frame
+    ///     scale (float): Axis length multiplier
+    ///
+    /// Raises:
+    ///     ValueError: If transform is not a finite 4x4 matrix
+    fn push_frame_scaled(&self, transform: PyReadonlyArray2<f32>, name: &str, scale: f32) -> PyResult<()> {
+        self.viewer
+            .push_frame_scaled(to_transform(&transform)?, name, scale)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Push a frame drawn with custom colors for its X, Y and Z axes.
+    ///
+    /// The colors are kept when the frame is later updated with push_frame.
+    ///
+    /// Args:
+    ///     transform (numpy.ndarray): A 4x4 homogeneous transformation matrix (float32)
+    ///     name (str): Unique identifier for the frame
+    ///     colors (list[list[float]]): RGBA colors of the X, Y and Z axes
+    ///
+    /// Raises:
+    ///     ValueError: If transform is not a finite 4x4 matrix
+    fn push_frame_colored(&self, transform: PyReadonlyArray2<f32>, name: &str, colors: [[f32; 4]; 3]) -> PyResult<()> {
+        self.viewer
+            .push_frame_colored(to_transform(&transform)?, name, colors)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Move a frame smoothly from its current transform to a new one.
+    ///
+    /// The translation is interpolated linearly and the rotation spherically.
+    /// A frame that does not exist yet is added at the target directly.
+    ///
+    /// Args:
+    ///     name (str): Name of the frame
+    ///     transform (numpy.ndarray): Target 4x4 homogeneous transformation matrix (float32)
+    ///     duration (float): Time to reach the target, in seconds
+    ///
+    /// Raises:
+    ///     ValueError: If transform is not a finite 4x4 matrix or duration is not positive
+    fn push_frame_interpolated(&self, name: &str, transform: PyReadonlyArray2<f32>, duration: f64) -> PyResult<()> {
+        self.viewer
+            .push_frame_interpolated(name, to_transform(&transform)?, to_duration(duration)?)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Set the appearance of a frame, kept when it is pushed again.
+    ///
+    /// Args:
+    ///     name (str): Name of the frame
+    ///     axis_length (float): Length of the axes in meters
+    ///     line_width (Optional[float]): Width of the axis lines in pixels,
+    ///         or None for the viewer's frame line width
+    ///     colors (Optional[list[list[float]]]): RGBA colors of the X, Y and Z axes,
+    ///         or None for the default colors
+    ///     alpha (float): Opacity multiplier
+    ///     marker_color (Optional[list[float]]): RGBA color of the origin marker, black by default
+    ///
+    /// Returns:
+    ///     bool: Whether the frame exists
+    #[pyo3(signature = (name, axis_length = 0.1, line_width = None, colors = None, alpha = 1.0, marker_color = None))]
+    fn style_frame(
+        &self,
+        name: &str,
+        axis_length: f32,
+        line_width: Option<f32>,
+        colors: Option<[[f32; 4]; 3]>,
+        alpha: f32,
+        marker_color: Option<[f32; 4]>,
+    ) -> bool {
+        let style = FrameStyle {
+            axis_length,
+            line_width,
+            colors,
+            alpha,
+            marker_color: marker_color.unwrap_or(FrameStyle::default().marker_color),
+        };
+        self.viewer.style_frame(name, style)
+    }
+
+    /// Change only the opacity of a frame, e.g. to fade out older frames.
+    ///
+    /// Args:
+    ///     name (str): Name of the frame
+    ///     alpha (float): Opacity multiplier, clamped to [0, 1]
+    ///
+    /// Returns:
+    ///     bool: Whether the frame exists
+    fn set_frame_alpha(&self, name: &str, alpha: f32) -> bool {
+        self.viewer.set_frame_alpha(name, alpha)
+    }
+
+    /// Hide or show a frame without removing it.
+    ///
+    /// The frame keeps its transform, style, trail and mesh, and pushing it
+    /// again does not change its visibility.
+    ///
+    /// Args:
+    ///     name (str): Name of the frame
+    ///     visible (bool): Whether to draw the frame
+    ///
+    /// Returns:
+    ///     bool: Whether the frame exists
+    fn set_frame_visible(&self, name: &str, visible: bool) -> bool {
+        self.viewer.set_frame_visible(name, visible)
+    }
+
+    /// Draw a frame over everything else in the scene, e.g. to highlight it.
+    ///
+    /// Pushing the frame again does not change this.
+    ///
+    /// Args:
+    ///     name (str): Name of the frame
+    ///     on_top (bool): Whether to draw the frame over everything else
+    ///
+    /// Returns:
+    ///     bool: Whether the frame exists
+    fn set_frame_always_on_top(&self, name: &str, on_top: bool) -> bool {
+        self.viewer.set_frame_always_on_top(name, on_top)
+    }
+
+    /// Show a frame as a ghost of its last known pose, faded with dashed axes,
+    /// e.g. when its data goes stale.
+    ///
+    /// Pushing the frame again makes it live again.
+    ///
+    /// Args:
+    ///     name (str): Name of the frame
+    ///     ghost (bool): Whether to show the frame as a ghost
+    ///
+    /// Returns:
+    ///     bool: Whether the frame exists
+    fn set_frame_ghost(&self, name: &str, ghost: bool) -> bool {
+        self.viewer.set_frame_ghost(name, ghost)
+    }
+
+    /// Remove a frame from the viewer.
+    ///
+    /// Args:
+    ///     name (str): Name of the frame
+    ///
+    /// Returns:
+    ///     bool: Whether the frame existed
+    fn remove_frame(&self, name: &str) -> bool {
+        self.viewer.remove_frame(name)
+    }
+
+    /// Rename a frame, keeping its transform.
+    ///
+    /// Args:
+    ///     old (str): Current name of the frame
+    ///     new (str): New name of the frame
+    ///
+    /// Returns:
+    ///     bool: False if `old` does not exist or `new` is already taken
+    fn rename_frame(&self, old: &str, new: &str) -> bool {
+        self.viewer.rename_frame(old, new)
+    }
+
+    /// Measure the angle between an axis of one frame and an axis of another.
+    ///
+    /// Both axes are taken in world coordinates.
+    ///
+    /// Args:
+    ///     a (str): Name of the first frame
+    ///     a_axis (str): Axis of the first frame ("x", "y" or "z")
+    ///     b (str): Name of the second frame
+    ///     b_axis (str): Axis of the second frame ("x", "y" or "z")
+    ///     degrees (bool): Return the angle in degrees instead of radians
+    ///
+    /// Returns:
+    ///     Optional[float]: The angle, or None if either frame does not exist
+    ///
+    /// Raises:
+    ///     ValueError: If an axis is not "x", "y" or "z"
+    #[pyo3(signature = (a, a_axis, b, b_axis, degrees = false))]
+    fn axis_angle(&self, a: &str, a_axis: &str, b: &str, b_axis: &str, degrees: bool) -> PyResult<Option<f32>> {
+        let angle = self.viewer.axis_angle(a, to_axis(a_axis)?, b, to_axis(b_axis)?);
+        Ok(if degrees { angle.map(f32::to_degrees) } else { angle })
+    }
+
+    /// Project a frame's origin to window pixel coordinates.
+    ///
+    /// Args:
+    ///     name (str): Name of the frame
+    ///
+    /// Returns:
+    ///     Optional[tuple[float, float]]: (x, y) in pixels from the top-left corner, or None
+    ///         if the frame does not exist or its origin is behind the camera or off-screen
+    fn project_to_screen(&self, name: &str) -> Option<(f32, f32)> {
+        self.viewer.project_to_screen(name)
+    }
+
+    /// Find the frame drawn under a window pixel.
+    ///
+    /// Frames hidden behind a mesh are not picked. Blocks until the next image
+    /// is drawn.
+    ///
+    /// Args:
+    ///     x (float): Pixels from the left of the window
+    ///     y (float): Pixels from the top of the window
+    ///
+    /// Returns:
+    ///     Optional[str]: Name of the frame drawn closest to (x, y) within a few
+    ///         pixels, or None (always None if the viewer is not running)
+    fn pick_frame(&self, py: Python, x: f32, y: f32) -> Option<String> {
+        py.allow_threads(|| self.viewer.pick_frame(x, y))
+    }
+
+    /// Name of the frame selected by the last left click in the window.
+    ///
+    /// Returns:
+    ///     Optional[str]: The clicked frame, or None if the last click was not on a frame
+    fn clicked_frame(&self) -> Option<String> {
+        self.viewer.clicked_frame()
+    }
+
+    /// Append a point to a trajectory, creating the trajectory if needed.
+    ///
+    /// Trajectories are drawn as polylines. Only the new point is sent to the
+    /// GPU, so growing a long path every tick stays cheap.
+    ///
+    /// Args:
+    ///     name (str): Unique identifier for the trajectory
+    ///     point (list[float]): [x, y, z] position in meters
+    fn append_trajectory_point(&self, name: &str, point: [f32; 3]) {
+        self.viewer.append_trajectory_point(name, point);
+    }
+
+    /// Draw text facing the camera at a world position, on top of the scene.
+    ///
+    /// Replaces any text of the same name.
+    ///
+    /// Args:
+    ///     name (str): Unique identifier for the text
+    ///     position (list[float]): [x, y, z] position of the text's bottom-left corner in meters
+    ///     text (str): Text to draw
+    ///     size (float): Text height in pixels
+    ///     color (Optional[list[float]]): RGBA color with components in [0, 1], black by default
+    #[pyo3(signature = (name, position, text, size = 14.0, color = None))]
+    fn push_text(&self, name: &str, position: [f32; 3], text: String, size: f32, color: Option<[f32; 4]>) {
+        self.viewer.push_text(name, position, text, size, color.unwrap_or([0.0, 0.0, 0.0, 1.0]));
+    }
+
+    /// Remove a text.
+    ///
+    /// Args:
+    ///     name (str): Name of the text
+    ///
+    /// Returns:
+    ///     bool: Whether the text existed
+    fn remove_text(&self, name: &str) -> bool {
+        self.viewer.remove_text(name)
+    }
+
+    /// Draw the 1-sigma ellipsoid of a position covariance.
+    ///
+    /// Replaces any ellipsoid of the same name.
+    ///
+    /// Args:
+    ///     name (str): Unique identifier for the ellipsoid
+    ///     center (list[float]): [x, y, z] center of the ellipsoid in meters
+    ///     covariance (numpy.ndarray): A symmetric 3x3 covariance matrix (float32) in square meters
+    ///     color (Optional[list[float]]): RGBA color with components in [0, 1], gray by default
+    ///
+    /// Raises:
+    ///     ValueError: If covariance is not a 3x3 matrix
+    #[pyo3(signature = (name, center, covariance, color = None))]
+    fn push_covariance(
+        &self,
+        name: &str,
+        center: [f32; 3],
+        covariance: PyReadonlyArray2<f32>,
+        color: Option<[f32; 4]>,
+    ) -> PyResult<()> {
+        let array = covariance.as_array();
+        if array.shape() != [3, 3] {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Covariance must be a 3x3 matrix",
+            ));
         }
+        let covariance = na::Matrix3::from_fn(|i, j| array[[i, j]]);
+        self.viewer.push_covariance(name, center, covariance, color.unwrap_or([0.5, 0.5, 0.5, 1.0]));
+        Ok(())
+    }
+
+    /// Remove a covariance ellipsoid.
+    ///
+    /// Args:
+    ///     name (str): Name of the ellipsoid
+    ///
+    /// Returns:
+    ///     bool: Whether the ellipsoid existed
+    fn remove_covariance(&self, name: &str) -> bool {
+        self.viewer.remove_covariance(name)
+    }
+
+    /// Draw a filled rectangle centered in the XY plane of a transform.
+    ///
+    /// Replaces any plane of the same name.
+    ///
+    /// Args:
+    ///     name (str): Unique identifier for the plane
+    ///     transform (numpy.ndarray): A 4x4 homogeneous transformation matrix (float32)
+    ///     width (float): Extent along the X axis in meters
+    ///     height (float): Extent along the Y axis in meters
+    ///     color (Optional[list[float]]): RGBA color with components in [0, 1],
+    ///         translucent gray by default
+    ///
+    /// Raises:
+    ///     ValueError: If transform is not a finite 4x4 matrix
+    #[pyo3(signature = (name, transform, width, height, color = None))]
+    fn push_plane(
+        &self,
+        name: &str,
+        transform: PyReadonlyArray2<f32>,
+        width: f32,
+        height: f32,
+        color: Option<[f32; 4]>,
+    ) -> PyResult<()> {
+        let color = color.unwrap_or([0.5, 0.5, 0.5, 0.3]);
+        self.viewer.push_plane(name, to_transform(&transform)?, width, height, color);
+        Ok(())
+    }
+
+    /// Remove a plane.
+    ///
+    /// Args:
+    ///     name (str): Name of the plane
+    ///
+    /// Returns:
+    ///     bool: Whether the plane existed
+    fn remove_plane(&self, name: &str) -> bool {
+        self.viewer.remove_plane(name)
+    }
 
-        self.viewer.push_frame(matrix, name);
+    /// Draw a point cloud in a single color.
+    ///
+    /// Replaces any point cloud of the same name.
+    ///
+    /// Args:
+    ///     name (str): Unique identifier for the point cloud
+    ///     points (numpy.ndarray): An Nx3 array (float32) of positions in meters
+    ///     color (Optional[list[float]]): RGBA color with components in [0, 1], dark gray by default
+    ///
+    /// Raises:
+    ///     ValueError: If points is not an Nx3 array
+    #[pyo3(signature = (name, points, color = None))]
+    fn push_points(&self, name: &str, points: PyReadonlyArray2<f32>, color: Option<[f32; 4]>) -> PyResult<()> {
+        let array = points.as_array();
+        if array.ncols() != 3 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Points must be an Nx3 array",
+            ));
+        }
+        let points: Vec<[f32; 3]> = array.rows().into_iter().map(|row| [row[0], row[1], row[2]]).collect();
+        self.viewer.push_points(name, &points, color.unwrap_or([0.2, 0.2, 0.2, 1.0]));
         Ok(())
     }
 
-    /// Remove all frames from the viewer.
+    /// Remove a point cloud.
     ///
-    /// This clears all frames currently being displayed in the viewer.
-    fn clear_frames(&self) {
-        self.viewer.clear_frames();
+    /// Args:
+    ///     name (str): Name of the point cloud
+    ///
+    /// Returns:
+    ///     bool: Whether the point cloud existed
+    fn remove_points(&self, name: &str) -> bool {
+        self.viewer.remove_points(name)
     }
 
-    /// Stop the viewer and close the window.
+    /// Draw a triangle mesh with the transform of a frame.
     ///
-    /// This stops the viewer thread and closes the visualization window.
-    fn stop(&self) {
-        self.viewer.stop();
+    /// Replaces any mesh attached to the same frame. The mesh is drawn once the
+    /// frame is pushed.
+    ///
+    /// Args:
+    ///     frame_name (str): Name of the frame the mesh moves with
+    ///     vertices (numpy.ndarray): An Nx3 array (float32) of positions in the frame's coordinates, in meters
+    ///     indices (numpy.ndarray): An Mx3 array (uint32) of vertex indices, one row per triangle
+    ///     color (Optional[list[float]]): RGBA color with components in [0, 1], light gray by default
+    ///
+    /// Raises:
+    ///     ValueError: If vertices or indices are not Nx3 arrays, or an index is out of bounds
+    #[pyo3(signature = (frame_name, vertices, indices, color = None))]
+    fn attach_mesh(
+        &self,
+        frame_name: &str,
+        vertices: PyReadonlyArray2<f32>,
+        indices: PyReadonlyArray2<u32>,
+        color: Option<[f32; 4]>,
+    ) -> PyResult<()> {
+        let (vertices, indices) = (vertices.as_array(), indices.as_array());
+        if vertices.ncols() != 3 || indices.ncols() != 3 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Vertices and indices must be Nx3 arrays",
+            ));
+        }
+        let vertices: Vec<[f32; 3]> = vertices.rows().into_iter().map(|row| [row[0], row[1], row[2]]).collect();
+        let indices: Vec<[u32; 3]> = indices.rows().into_iter().map(|row| [row[0], row[1], row[2]]).collect();
+        self.viewer
+            .attach_mesh(frame_name, &vertices, &indices, color.unwrap_or(MESH_COLOR))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Load a Wavefront OBJ file and draw it with the transform of a frame.
+    ///
+    /// Replaces any mesh attached to the same frame. Materials are ignored.
+    ///
+    /// Args:
+    ///     frame_name (str): Name of the frame the mesh moves with
+    ///     path (str): Path of the OBJ file, with positions in the frame's coordinates, in meters
+    ///     color (Optional[list[float]]): RGBA color with components in [0, 1], light gray by default
+    ///
+    /// Raises:
+    ///     RuntimeError: If the file cannot be read or parsed
+    #[pyo3(signature = (frame_name, path, color = None))]
+    fn attach_obj(&self, frame_name: &str, path: &str, color: Option<[f32; 4]>) -> PyResult<()> {
+        self.viewer.attach_obj(frame_name, path, color.unwrap_or(MESH_COLOR)).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to load '{}': {}", path, e))
+        })
+    }
+
+    /// Remove the mesh attached to a frame.
+    ///
+    /// Args:
+    ///     frame_name (str): Name of the frame
+    ///
+    /// Returns:
+    ///     bool: Whether the frame had a mesh
+    fn detach_mesh(&self, frame_name: &str) -> bool {
+        self.viewer.detach_mesh(frame_name)
+    }
+
+    /// Draw line segments in a single color.
+    ///
+    /// Replaces any set of segments of the same name.
+    ///
+    /// Args:
+    ///     name (str): Unique identifier for the segments
+    ///     segments (numpy.ndarray): An Nx2x3 array (float32) of segment start and end positions in meters
+    ///     color (Optional[list[float]]): RGBA color with components in [0, 1], dark gray by default
+    ///
+    /// Raises:
+    ///     ValueError: If segments is not an Nx2x3 array
+    #[pyo3(signature = (name, segments, color = None))]
+    fn push_lines(&self, name: &str, segments: PyReadonlyArray3<f32>, color: Option<[f32; 4]>) -> PyResult<()> {
+        let array = segments.as_array();
+        if array.shape()[1..] != [2, 3] {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Segments must be an Nx2x3 array",
+            ));
+        }
+        let segments: Vec<([f32; 3], [f32; 3])> = array
+            .outer_iter()
+            .map(|segment| {
+                let point = |i: usize| [segment[[i, 0]], segment[[i, 1]], segment[[i, 2]]];
+                (point(0), point(1))
+            })
+            .collect();
+        self.viewer.push_lines(name, &segments, color.unwrap_or([0.2, 0.2, 0.2, 1.0]));
+        Ok(())
     }
+
+    /// Remove a set of line segments.
+    ///
+    /// Args:
+    ///     name (str): Name of the segments
+    ///
+    /// Returns:
+    ///     bool: Whether the segments existed
+    fn remove_lines(&self, name: &str) -> bool {
+        self.viewer.remove_lines(name)
+    }
+
+    /// Resize the world grid.
+    ///
+    /// Args:
+    ///     size (float): Extent of the grid in meters (default 1.0)
+    ///     step (float): Spacing between grid lines in meters (default 0.1)
+    ///
+    /// Raises:
+    ///     ValueError: Unless size and step are finite and positive, with at most
+    ///         10000 lines along each axis
+    fn set_grid(&self, size: f32, step: f32) -> PyResult<()> {
+        if !self.viewer.set_grid(size, step) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Grid size and step must be finite and positive, with at most 10000 lines",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Show or hide the world grid.
+    ///
+    /// Args:
+    ///     visible (bool): Whether to draw the world grid
+    fn set_grid_visible(&self, visible: bool) {
+        self.viewer.set_grid_visible(visible);
+    }
+
+    /// Draw the world grid in the given planes only.
+    ///
+    /// Args:
+    ///     planes (list[GridPlane]): e.g. [GridPlane.XY] for just the floor of a Z-up scene
+    fn set_grid_planes(&self, planes: Vec<PyGridPlane>) {
+        let selected = planes.into_iter().fold(GridPlanes::NONE, |selected, plane| {
+            selected
+                | match plane {
+                    PyGridPlane::XY => GridPlanes::XY,
+                    PyGridPlane::XZ => GridPlanes::XZ,
+                    PyGridPlane::YZ => GridPlanes::YZ,
+                }
+        });
+        self.viewer.set_grid_planes(selected);
+    }
+
+    /// Draw the world grid with solid or dashed lines.
+    ///
+    /// Args:
+    ///     style (str): "solid" or "dashed"
+    ///
+    /// Raises:
+    ///     ValueError: If the style is not "solid" or "dashed"
+    fn set_grid_style(&self, style: &str) -> PyResult<()> {
+        let style = match style.to_ascii_lowercase().as_str() {
+            "solid" => GridStyle::Solid,
+            "dashed" => GridStyle::Dashed,
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "Grid style must be 'solid' or 'dashed'",
+                ))
+            }
+        };
+        self.viewer.set_grid_style(style);
+        Ok(())
+    }
+
+    /// Draw an additional grid anchored at a transform.
+    ///
+    /// Useful to show several reference frames (e.g. map and odom) side by side.
+    /// Replaces any grid of the same name.
+    ///
+    /// Args:
+    ///     name (str): Unique identifier for the grid
+    ///     transform (numpy.ndarray): A 4x4 homogeneous transformation matrix (float32)
+    ///     size (float): Extent of the grid in meters
+    ///     step (float): Spacing between grid lines in meters
+    ///
+    /// Raises:
+    ///     ValueError: If transform is not a finite 4x4 matrix, or size and step
+    ///         are not finite and positive with at most 10000 lines along each axis
+    #[pyo3(signature = (name, transform, size = 1.0, step = 0.1))]
+    fn add_grid(&self, name: &str, transform: PyReadonlyArray2<f32>, size: f32, step: f32) -> PyResult<()> {
+        let config = GridConfig {
+            size,
+            step,
+            ..GridConfig::default()
+        };
+        if !self.viewer.add_grid(name, to_transform(&transform)?, config) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Grid size and step must be finite and positive, with at most 10000 lines",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Remove an anchored grid.
+    ///
+    /// Args:
+    ///     name (str): Name of the grid
+    ///
+    /// Returns:
+    ///     bool: Whether the grid existed
+    fn remove_grid(&self, name: &str) -> bool {
+        self.viewer.remove_grid(name)
+    }
+
+    /// Trace the path of a frame's origin as it moves.
+    ///
+    /// Resets the trail if it was already enabled.
+    ///
+    /// Args:
+    ///     name (str): Name of the frame
+    ///     max_points (int): Number of latest positions kept, older ones are dropped
+    #[pyo3(signature = (name, max_points = 500))]
+    fn enable_trail(&self, name: &str, max_points: usize) {
+        self.viewer.enable_trail(name, max_points);
+    }
+
+    /// Stop tracing a frame's path and clear its trail.
+    ///
+    /// Args:
+    ///     name (str): Name of the frame
+    ///
+    /// Returns:
+    ///     bool: Whether the trail was enabled
+    fn disable_trail(&self, name: &str) -> bool {
+        self.viewer.disable_trail(name)
+    }
+
+    /// Remove a trajectory.
+    ///
+    /// Args:
+    ///     name (str): Name of the trajectory
+    ///
+    /// Returns:
+    ///     bool: Whether the trajectory existed
+    fn remove_trajectory(&self, name: &str) -> bool {
+        self.viewer.remove_trajectory(name)
+    }
+
+    /// Get the names of the frames currently in the viewer.
+    ///
+    /// Returns:
+    ///     list[str]: Frame names, in no particular order
+    fn frame_names(&self) -> Vec<String> {
+        self.viewer.frame_names()
+    }
+
+    /// Get the current transform of a frame.
+    ///
+    /// Args:
+    ///     name (str): Name of the frame
+    ///
+    /// Returns:
+    ///     Optional[numpy.ndarray]: A 4x4 homogeneous transformation matrix (float32),
+    ///         or None if the frame does not exist
+    fn get_frame<'py>(&self, py: Python<'py>, name: &str) -> PyResult<Option<&'py PyArray2<f32>>> {
+        self.viewer.get_frame(name).map(|transform| to_pyarray(py, &transform)).transpose()
+    }
+
+    /// Get the time elapsed since a frame was last pushed.
+    ///
+    /// Args:
+    ///     name (str): Name of the frame
+    ///
+    /// Returns:
+    ///     Optional[float]: Age in seconds, or None if the frame does not exist
+    fn frame_age(&self, name: &str) -> Option<f64> {
+        self.viewer.frame_age(name).map(|age| age.as_secs_f64())
+    }
+
+    /// Fade frames out as they age.
+    ///
+    /// A frame's opacity halves every `half_life` seconds since it was last pushed.
+    ///
+    /// Args:
+    ///     half_life (Optional[float]): Half-life in seconds, or None to disable fading
+    ///
+    /// Raises:
+    ///     ValueError: If half_life is not positive
+    fn set_age_fade(&self, half_life: Option<f64>) -> PyResult<()> {
+        let half_life = half_life.map(to_duration).transpose()?;
+        self.viewer.set_age_fade(half_life);
+        Ok(())
+    }
+
+    /// Hide frames that have not been pushed recently.
+    ///
+    /// Hidden frames are kept and show up again as soon as they are pushed.
+    ///
+    /// Args:
+    ///     threshold (Optional[float]): Age in seconds after which frames are hidden,
+    ///         or None to show all frames
+    ///
+    /// Raises:
+    ///     ValueError: If threshold is not positive
+    fn set_stale_hide(&self, threshold: Option<f64>) -> PyResult<()> {
+        let threshold = threshold.map(to_duration).transpose()?;
+        self.viewer.set_stale_hide(threshold);
+        Ok(())
+    }
+
+    /// Briefly enlarge a frame's axes each time it is pushed.
+    ///
+    /// The axes are drawn at twice their length right after an update and
+    /// shrink back to normal over `duration` seconds.
+    ///
+    /// Args:
+    ///     duration (Optional[float]): Pulse duration in seconds, or None to disable it
+    ///
+    /// Raises:
+    ///     ValueError: If duration is not positive
+    fn set_update_pulse(&self, duration: Option<f64>) -> PyResult<()> {
+        let duration = duration.map(to_duration).transpose()?;
+        self.viewer.set_update_pulse(duration);
+        Ok(())
+    }
+
+    /// Remove all frames from the viewer.
+    ///
+    /// Point clouds, lines, meshes and other overlays are left in place.
+    fn clear_frames(&self) {
+        self.viewer.clear_frames();
+    }
+
+    /// Remove every point cloud.
+    fn clear_points(&self) {
+        self.viewer.clear_points();
+    }
+
+    /// Remove every set of line segments.
+    fn clear_lines(&self) {
+        self.viewer.clear_lines();
+    }
+
+    /// Detach every mesh from its frame.
+    fn clear_meshes(&self) {
+        self.viewer.clear_meshes();
+    }
+
+    /// Empty the scene.
+    ///
+    /// Removes frames and everything drawn alongside them: trajectories, trails,
+    /// anchored grids, texts, covariances, planes, points, lines and meshes.
+    /// The world grid and other settings are left untouched.
+    fn clear_all(&self) {
+        self.viewer.clear_all();
+    }
+
+    /// Save frames to a TUM trajectory file.
+    ///
+    /// Args:
+    ///     path (str): Output file path
+    ///     name_prefix (str): Only frames whose name starts with this prefix are saved
+    ///
+    /// Raises:
+    ///     OSError: If the file cannot be written
+    #[pyo3(signature = (path, name_prefix = ""))]
+    fn save_trajectory_tum(&self, path: &str, name_prefix: &str) -> PyResult<()> {
+        self.viewer.save_trajectory_tum(path, name_prefix).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyOSError, _>(format!("Failed to save trajectory: {}", e))
+        })
+    }
+
+    /// Save the next rendered image as a PNG file.
+    ///
+    /// Blocks until the image is written. The image has the render resolution,
+    /// so it is supersampled when the render scale is above 1.
+    ///
+    /// Args:
+    ///     path (str): Output file path
+    ///
+    /// Raises:
+    ///     RuntimeError: If the viewer is not running or the file cannot be written
+    fn screenshot(&self, py: Python, path: &str) -> PyResult<()> {
+        py.allow_threads(|| self.viewer.screenshot(path)).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to save screenshot: {}", e))
+        })
+    }
+
+    /// Block until the viewer stops, e.g. because the user closed the window.
+    ///
+    /// Args:
+    ///     timeout (Optional[float]): Longest time to wait in seconds, or None to wait forever
+    ///
+    /// Returns:
+    ///     bool: Whether the viewer is stopped, False if the timeout elapsed first
+    #[pyo3(signature = (timeout = None))]
+    fn wait_for_close(&self, py: Python, timeout: Option<f32>) -> bool {
+        // Subscribe before checking, so that a close in between is not missed
+        let signal = self.viewer.close_signal();
+        if !self.viewer.is_running() {
+            return true;
+        }
+        py.allow_threads(move || match timeout {
+            Some(timeout) => {
+                let timeout = Duration::try_from_secs_f32(timeout).unwrap_or_default();
+                signal.recv_timeout(timeout) != Err(std::sync::mpsc::RecvTimeoutError::Timeout)
+            }
+            // Disconnected also means the render thread is gone
+            None => {
+                let _ = signal.recv();
+                true
+            }
+        })
+    }
+
+    /// Start recording the rendered images to a video file.
+    ///
+    /// Requires `ffmpeg` on the PATH. The format follows the file extension
+    /// (e.g. .mp4 or .gif), and the video runs at `fps` whatever the render rate.
+    ///
+    /// Args:
+    ///     path (str): Output video path
+    ///     fps (float, optional): Frames per second of the video. Defaults to 30
+    ///
+    /// Raises:
+    ///     RuntimeError: If fps is not positive, or a previous recording failed
+    #[pyo3(signature = (path, fps = 30.0))]
+    fn start_recording(&self, py: Python, path: &str, fps: f32) -> PyResult<()> {
+        py.allow_threads(|| self.viewer.start_recording(path, fps)).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to start recording: {}", e))
+        })
+    }
+
+    /// Stop recording and wait until the video file is written.
+    ///
+    /// Raises:
+    ///     RuntimeError: If the video could not be recorded
+    fn stop_recording(&self, py: Python) -> PyResult<()> {
+        py.allow_threads(|| self.viewer.stop_recording()).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to record video: {}", e))
+        })
+    }
+
+    /// Load a recorded sequence of frame sets for playback.
+    ///
+    /// Playback starts paused on the first sample. Use `play`, `pause`,
+    /// `seek` and `set_speed` to control it.
+    ///
+    /// Args:
+    ///     frames_over_time (list[tuple[float, dict[str, numpy.ndarray]]]): Samples
+    ///         as (timestamp in seconds, {name: 4x4 transform}) pairs
+    ///
+    /// Raises:
+    ///     ValueError: If any transform is not a finite 4x4 matrix
+    fn load_sequence(&self, frames_over_time: Vec<(f64, HashMap<String, PyReadonlyArray2<f32>>)>) -> PyResult<()> {
+        let mut samples = Vec::with_capacity(frames_over_time.len());
+        for (time, frames) in frames_over_time {
+            let mut transforms = HashMap::with_capacity(frames.len());
+            for (name, transform) in frames {
+                transforms.insert(name, to_transform(&transform)?);
+            }
+            samples.push((time, transforms));
+        }
+
+        self.viewer.load_sequence(samples);
+        Ok(())
+    }
+
+    /// Start or resume playback of the loaded sequence.
+    fn play(&self) {
+        self.viewer.play();
+    }
+
+    /// Pause playback of the loaded sequence.
+    fn pause(&self) {
+        self.viewer.pause();
+    }
+
+    /// Jump to a time in the loaded sequence.
+    ///
+    /// Args:
+    ///     time (float): Timestamp in seconds, clamped to the sequence bounds
+    fn seek(&self, time: f64) {
+        self.viewer.seek(time);
+    }
+
+    /// Set the playback speed.
+    ///
+    /// Args:
+    ///     speed (float): Speed multiplier (1.0 is real time, negative plays backwards)
+    fn set_speed(&self, speed: f64) {
+        self.viewer.set_speed(speed);
+    }
+
+    /// Current time of the playback clock.
+    ///
+    /// Returns:
+    ///     float: Timestamp in seconds
+    fn playback_time(&self) -> f64 {
+        self.viewer.playback_time()
+    }
+
+    /// Whether the loaded sequence is currently playing.
+    fn is_playing(&self) -> bool {
+        self.viewer.is_playing()
+    }
+
+    /// Set the handedness of the coordinate system frames are expressed in.
+    ///
+    /// With "left", the whole scene is mirrored along the world Z axis so data
+    /// from left-handed engines (e.g. Unity) displays without flipping every
+    /// transform.
+    ///
+    /// Args:
+    ///     handedness (str): "right" (default) or "left"
+    ///
+    /// Raises:
+    ///     ValueError: If handedness is not "right" or "left"
+    fn set_handedness(&self, handedness: &str) -> PyResult<()> {
+        let handedness = match handedness.to_ascii_lowercase().as_str() {
+            "right" => Handedness::RightHanded,
+            "left" => Handedness::LeftHanded,
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "Handedness must be 'right' or 'left'",
+                ))
+            }
+        };
+        self.viewer.set_handedness(handedness);
+        Ok(())
+    }
+
+    /// Set the world up direction.
+    ///
+    /// The camera orbits around it and the grid is drawn on the plane perpendicular to it.
+    ///
+    /// Args:
+    ///     up (list[float]): [x, y, z] up direction, [0, 0, 1] (Z-up) by default or e.g. [0, 1, 0] for Y-up
+    ///
+    /// Raises:
+    ///     ValueError: If up is zero or not finite
+    fn set_world_up(&self, up: [f32; 3]) -> PyResult<()> {
+        if !self.viewer.set_world_up(up) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "World up must be a finite, non-zero vector",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Set the camera's vertical field of view.
+    ///
+    /// Args:
+    ///     fov (float): Field of view in radians, clamped to about [1, 170] degrees
+    fn set_fov(&self, fov: f32) {
+        self.viewer.set_fov(fov);
+    }
+
+    /// Choose what zooming with the mouse wheel or the + and - keys changes.
+    ///
+    /// Args:
+    ///     mode (str): "dolly" (default) moves the camera towards its target,
+    ///         "fov" narrows the field of view instead
+    ///
+    /// Raises:
+    ///     ValueError: If mode is not "dolly" or "fov"
+    fn set_zoom_mode(&self, mode: &str) -> PyResult<()> {
+        let mode = match mode.to_ascii_lowercase().as_str() {
+            "dolly" => ZoomMode::Dolly,
+            "fov" => ZoomMode::Fov,
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "Zoom mode must be 'dolly' or 'fov'",
+                ))
+            }
+        };
+        self.viewer.set_zoom_mode(mode);
+        Ok(())
+    }
+
+    /// Look at the camera target from a canonical viewpoint, keeping the distance to it.
+    ///
+    /// Pressing 1, 3 or 7 on the numeric keypad selects the front, side and top views.
+    ///
+    /// Args:
+    ///     view (str): "front", "side" or "top"
+    ///
+    /// Raises:
+    ///     ValueError: If view is not "front", "side" or "top"
+    fn set_standard_view(&self, view: &str) -> PyResult<()> {
+        let view = match view.to_ascii_lowercase().as_str() {
+            "front" => StandardView::Front,
+            "side" => StandardView::Side,
+            "top" => StandardView::Top,
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "View must be 'front', 'side' or 'top'",
+                ))
+            }
+        };
+        self.viewer.set_standard_view(view);
+        Ok(())
+    }
+
+    /// Point the camera at everything in the scene, from its current direction.
+    ///
+    /// Frames, point clouds, lines and meshes are all brought into view.
+    /// Pressing F in the window does the same.
+    ///
+    /// Returns:
+    ///     bool: False if the scene is empty, in which case the camera is unchanged
+    fn fit_view(&self) -> bool {
+        self.viewer.fit_view()
+    }
+
+    /// Switch to a top-down view for planar data.
+    ///
+    /// The camera looks straight down at the ground plane through an orthographic
+    /// projection, and dragging with any mouse button pans instead of orbiting.
+    /// While it is on, standard views and projection changes are ignored, and
+    /// resetting or placing the camera keeps it looking down. Turning it off
+    /// restores the previous view.
+    ///
+    /// Args:
+    ///     enabled (bool): Whether to use the top-down view
+    fn set_2d_mode(&self, enabled: bool) {
+        self.viewer.set_2d_mode(enabled);
+    }
+
+    /// Set the distances from the camera beyond which geometry is clipped.
+    ///
+    /// Depth precision depends on far / near, and mostly on near: when
+    /// overlapping lines flicker (z-fighting), raise near as much as the closest
+    /// geometry allows and keep far just beyond the farthest.
+    ///
+    /// Args:
+    ///     near (float): Near clipping distance in meters, 0.1 by default
+    ///     far (float): Far clipping distance in meters, 100 by default
+    ///
+    /// Raises:
+    ///     ValueError: Unless 0 < near < far
+    fn set_clip_planes(&self, near: f32, far: f32) -> PyResult<()> {
+        if !self.viewer.set_clip_planes(near, far) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Clip planes must satisfy 0 < near < far",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Limit how close to and far from its target the camera can zoom.
+    ///
+    /// Args:
+    ///     min_distance (float): Closest distance to the target in meters
+    ///     max_distance (float): Farthest distance to the target in meters
+    ///
+    /// Raises:
+    ///     ValueError: Unless 0 < min_distance <= max_distance
+    fn set_zoom_limits(&self, min_distance: f32, max_distance: f32) -> PyResult<()> {
+        if !self.viewer.set_zoom_limits(min_distance, max_distance) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Zoom limits must satisfy 0 < min_distance <= max_distance",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Choose the mouse button panning the camera when dragged, in addition to the middle one.
+    ///
+    /// Args:
+    ///     button (str): "right" (default) or "middle"
+    ///
+    /// Raises:
+    ///     ValueError: If button is not "right" or "middle"
+    fn set_pan_button(&self, button: &str) -> PyResult<()> {
+        let button = match button.to_ascii_lowercase().as_str() {
+            "right" => MouseButton::Right,
+            "middle" => MouseButton::Middle,
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "Pan button must be 'right' or 'middle'",
+                ))
+            }
+        };
+        self.viewer.set_pan_button(button);
+        Ok(())
+    }
+
+    /// Set how far the keyboard nudges the camera per key press.
+    ///
+    /// Args:
+    ///     orbit (float): Radians of orbit per arrow key press (default 0.05)
+    ///     zoom (float): Zoom per + or - press, in mouse wheel notches (default 0.25)
+    fn set_keyboard_steps(&self, orbit: f32, zoom: f32) {
+        self.viewer.set_keyboard_steps(orbit, zoom);
+    }
+
+    /// Switch between perspective and orthographic projection.
+    ///
+    /// Pressing O in the window toggles between the two. In orthographic mode
+    /// zooming changes the visible height instead of moving the camera.
+    ///
+    /// Args:
+    ///     mode (str): "perspective" (default) or "orthographic"
+    ///     height (float): Visible vertical extent in meters in orthographic mode
+    ///
+    /// Raises:
+    ///     ValueError: If mode is not "perspective" or "orthographic"
+    #[pyo3(signature = (mode, height=2.0))]
+    fn set_projection_mode(&self, mode: &str, height: f32) -> PyResult<()> {
+        let mode = match mode.to_ascii_lowercase().as_str() {
+            "perspective" => ProjectionMode::Perspective,
+            "orthographic" => ProjectionMode::Orthographic { height },
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "Projection mode must be 'perspective' or 'orthographic'",
+                ))
+            }
+        };
+        self.viewer.set_projection_mode(mode);
+        Ok(())
+    }
+
+    /// Place the camera, e.g. to script a flythrough or restore a saved viewpoint.
+    ///
+    /// Args:
+    ///     position (list[float]): [x, y, z] camera position in meters
+    ///     target (list[float]): [x, y, z] point the camera looks at and orbits around
+    ///     up (Optional[list[float]]): Direction pointing up on screen, the current one by default
+    ///
+    /// Raises:
+    ///     ValueError: If the pose is not finite or looks along the up direction
+    #[pyo3(signature = (position, target, up = None))]
+    fn set_camera(&self, position: [f32; 3], target: [f32; 3], up: Option<[f32; 3]>) -> PyResult<()> {
+        let up = up.unwrap_or(self.viewer.camera_pose().2);
+        if !self.viewer.set_camera_pose(position, target, up) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Camera pose must be finite and not look along the up direction",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get the current camera pose.
+    ///
+    /// Returns:
+    ///     dict: "position", "target" and "up", each an [x, y, z] list
+    fn get_camera(&self, py: Python) -> PyResult<PyObject> {
+        let (position, target, up) = self.viewer.camera_pose();
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("position", position.to_vec())?;
+        dict.set_item("target", target.to_vec())?;
+        dict.set_item("up", up.to_vec())?;
+        Ok(dict.into())
+    }
+
+    /// Orbit around a frame's origin, keeping the camera's distance and orientation.
+    ///
+    /// Args:
+    ///     name (str): Name of the frame
+    ///
+    /// Returns:
+    ///     bool: Whether the frame exists
+    fn focus_on_frame(&self, name: &str) -> bool {
+        self.viewer.focus_on_frame(name)
+    }
+
+    /// Move the camera back to its initial viewpoint.
+    ///
+    /// Pressing R in the window does the same.
+    fn reset_camera(&self) {
+        self.viewer.reset_camera();
+    }
+
+    /// Get the view matrix the viewer is currently rendering with.
+    ///
+    /// Returns:
+    ///     numpy.ndarray: A 4x4 matrix (float32) mapping world to camera coordinates
+    fn view_matrix<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<f32>> {
+        to_pyarray(py, &self.viewer.view_matrix())
+    }
+
+    /// Get the projection matrix the viewer is currently rendering with.
+    ///
+    /// Returns:
+    ///     numpy.ndarray: A 4x4 matrix (float32) mapping camera to clip coordinates
+    fn projection_matrix<'py>(&self, py: Python<'py>) -> PyResult<&'py PyArray2<f32>> {
+        to_pyarray(py, &self.viewer.projection_matrix())
+    }
+
+    /// Color each frame's axes by a hash of its name.
+    ///
+    /// The X, Y and Z axes of a frame get decreasing shades of the same hue,
+    /// which makes many frames easier to tell apart than red/green/blue.
+    ///
+    /// Args:
+    ///     enabled (bool): Whether to color frames by name
+    fn set_color_by_name(&self, enabled: bool) {
+        self.viewer.set_color_by_name(enabled);
+    }
+
+    /// Set the seed of the name to color hash.
+    ///
+    /// The mapping is stable for a given seed; another seed reshuffles it.
+    ///
+    /// Args:
+    ///     seed (int): Hash seed (default 0)
+    fn set_color_seed(&self, seed: u64) {
+        self.viewer.set_color_seed(seed);
+    }
+
+    /// Set the length of the world origin axes.
+    ///
+    /// This only affects the origin frame, so it can be emphasized while
+    /// data frames keep their size.
+    ///
+    /// Args:
+    ///     length (float): Axis length in meters (default 0.1)
+    fn set_origin_axis_length(&self, length: f32) {
+        self.viewer.set_origin_axis_length(length);
+    }
+
+    /// Set the default length of the frame axes.
+    ///
+    /// Frames pushed with a scale keep their size relative to this default.
+    ///
+    /// Args:
+    ///     length (float): Axis length in meters (default 0.1)
+    ///
+    /// Raises:
+    ///     ValueError: If length is not positive
+    fn set_axis_length(&self, length: f32) -> PyResult<()> {
+        if !self.viewer.set_axis_length(length) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Axis length must be positive",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Set the width of the frame axes and of the grid lines.
+    ///
+    /// Frames styled with their own line width keep it. Drivers may clamp
+    /// widths above 1 pixel to the largest width they support.
+    ///
+    /// Args:
+    ///     frame (float): Width of the frame axes in pixels (default 3.0)
+    ///     grid (float): Width of the grid lines in pixels (default 1.0)
+    fn set_line_widths(&self, frame: f32, grid: f32) {
+        self.viewer.set_line_widths(frame, grid);
+    }
+
+    /// Set the color the window is cleared to.
+    ///
+    /// Args:
+    ///     r (float): Red component in [0, 1]
+    ///     g (float): Green component in [0, 1]
+    ///     b (float): Blue component in [0, 1]
+    ///     a (float): Alpha component in [0, 1] (default 1.0)
+    #[pyo3(signature = (r, g, b, a = 1.0))]
+    fn set_background_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.viewer.set_background_color(r, g, b, a);
+    }
+
+    /// Set the resolution multiplier used for rendering.
+    ///
+    /// The scene is rendered at `scale` times the window resolution and
+    /// downsampled, so 2.0 supersamples away aliasing on lines.
+    ///
+    /// Args:
+    ///     scale (float): Render scale, clamped to [0.25, 4.0] (default 1.0)
+    fn set_render_scale(&self, scale: f32) {
+        self.viewer.set_render_scale(scale);
+    }
+
+    /// Draw a faint dashed line from every frame origin to the world origin.
+    ///
+    /// Args:
+    ///     visible (bool): Whether to draw the tethers
+    fn set_origin_tethers(&self, visible: bool) {
+        self.viewer.set_origin_tethers(visible);
+    }
+
+    /// Hide the grids, origin axes, origin tethers, scale bar and axis indicator, e.g. for screenshots.
+    ///
+    /// The individual settings are kept and apply again once this is turned off.
+    /// Pressing H in the window toggles it too.
+    ///
+    /// Args:
+    ///     enabled (bool): Whether presentation mode is on
+    fn set_presentation_mode(&self, enabled: bool) {
+        self.viewer.set_presentation_mode(enabled);
+    }
+
+    /// Draw a dot at each frame's origin, easier to spot than the axes from afar.
+    ///
+    /// The dot's color is set per frame with style_frame.
+    ///
+    /// Args:
+    ///     visible (bool): Whether to draw the markers
+    fn set_origin_markers(&self, visible: bool) {
+        self.viewer.set_origin_markers(visible);
+    }
+
+    /// Draw a label at the world origin.
+    ///
+    /// Args:
+    ///     label (Optional[str]): Text such as "world", or None to remove the label
+    fn set_origin_label(&self, label: Option<&str>) {
+        self.viewer.set_origin_label(label);
+    }
+
+    /// Label the world grid's axes with their distance from the origin.
+    ///
+    /// A label is drawn every five grid lines, i.e. every 50 centimeters with the default grid.
+    ///
+    /// Args:
+    ///     visible (bool): Whether to draw the tick labels (default False)
+    fn set_grid_ticks(&self, visible: bool) {
+        self.viewer.set_grid_ticks(visible);
+    }
+
+    /// Show or hide the frame name labels.
+    ///
+    /// Each frame's name is drawn next to its origin, facing the camera.
+    ///
+    /// Args:
+    ///     visible (bool): Whether to draw the labels (default True)
+    fn set_show_labels(&self, visible: bool) {
+        self.viewer.set_show_labels(visible);
+    }
+
+    /// Show or hide the scale bar.
+    ///
+    /// The scale bar in the bottom-left corner shows the on-screen length of
+    /// 1 meter at the camera target, with ticks every 10 centimeters.
+    ///
+    /// Args:
+    ///     visible (bool): Whether to draw the scale bar
+    fn set_scale_bar(&self, visible: bool) {
+        self.viewer.set_scale_bar(visible);
+    }
+
+    /// Show or hide the axis indicator.
+    ///
+    /// The indicator in the bottom-right corner turns with the camera to show
+    /// the orientation of the world X, Y and Z axes.
+    ///
+    /// Args:
+    ///     visible (bool): Whether to draw the indicator
+    fn set_gnomon(&self, visible: bool) {
+        self.viewer.set_gnomon(visible);
+    }
+
+    /// List the monitors connected when the window was opened.
+    ///
+    /// Returns:
+    ///     list[str]: Monitor names, empty until the viewer is started
+    fn list_monitors(&self) -> Vec<String> {
+        self.viewer.list_monitors()
+    }
+
+    /// Move the window to the center of another monitor.
+    ///
+    /// Args:
+    ///     index (int): Index of the monitor in `list_monitors()`
+    ///
+    /// Returns:
+    ///     bool: False if there is no such monitor
+    fn move_to_monitor(&self, index: usize) -> bool {
+        self.viewer.move_to_monitor(index)
+    }
+
+    /// Restore the default look, keeping frame transforms.
+    ///
+    /// Resets the background, world grid, axis colors, overlays and every
+    /// frame's style.
+    fn reset_appearance(&self) {
+        self.viewer.reset_appearance();
+    }
+
+    /// Get the timing of the render loop, averaged over recent frames.
+    ///
+    /// Returns:
+    ///     dict: "fps" (frames per second), "frame_time" (seconds spent drawing a frame)
+    ///         and "dropped" (number of frames that took longer than 33 ms)
+    fn render_stats(&self, py: Python) -> PyResult<PyObject> {
+        let stats = self.viewer.render_stats();
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("fps", stats.fps)?;
+        dict.set_item("frame_time", stats.frame_time.as_secs_f64())?;
+        dict.set_item("dropped", stats.dropped)?;
+        Ok(dict.into())
+    }
+
+    /// Get the time between the last two rendered frames.
+    ///
+    /// Returns:
+    ///     Optional[float]: Seconds between the last two frames, or None before the first frame
+    fn last_frame_time(&self) -> Option<f64> {
+        self.viewer.last_frame_time().map(|interval| interval.as_secs_f64())
+    }
+
+    /// Show or hide the frame rate and frame time in the top-left corner.
+    ///
+    /// Args:
+    ///     visible (bool): Whether to draw the overlay
+    fn set_fps_overlay(&self, visible: bool) {
+        self.viewer.set_fps_overlay(visible);
+    }
+
+    /// Throttle the frame rate to limit the render thread's CPU usage.
+    ///
+    /// Args:
+    ///     usage (Optional[float]): Maximum share of time spent drawing, in (0, 1],
+    ///         or None to draw as fast as possible
+    fn set_max_cpu_usage(&self, usage: Option<f32>) {
+        self.viewer.set_max_cpu_usage(usage);
+    }
+
+    /// Check whether the viewer is started and its window is still open.
+    ///
+    /// Returns:
+    ///     bool: False before start, and after the window is closed or stop is called
+    fn is_running(&self) -> bool {
+        self.viewer.is_running()
+    }
+
+    /// Stop the viewer and close the window.
+    ///
+    /// This stops the viewer thread and closes the visualization window.
+    fn stop(&self) {
+        self.viewer.stop();
+    }
+
+    /// Start the viewer when entering a `with` block.
+    ///
+    /// Raises:
+    ///     RuntimeError: If the viewer fails to start
+    fn __enter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        slf.start()?;
+        Ok(slf)
+    }
+
+    /// Stop the viewer and close the window when leaving a `with` block.
+    ///
+    /// Exceptions raised in the block are propagated.
+    fn __exit__(&self, _exc_type: &PyAny, _exc_value: &PyAny, _traceback: &PyAny) -> bool {
+        self.viewer.stop();
+        false
+    }
+}
+
+/// Convert a 4x4 numpy array into a transform, checking its shape.
+fn to_transform<T>(transform: &PyReadonlyArray2<T>) -> PyResult<na::Matrix4<T>>
+where
+    T: numpy::Element + na::RealField + Copy,
+{
+    let array = transform.as_array();
+    if array.shape() != [4, 4] {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "Transform must be a 4x4 matrix",
+        ));
+    }
+    if !array.iter().all(|v| v.is_finite()) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "Transform must not contain NaN or infinite values",
+        ));
+    }
+
+    let mut matrix = na::Matrix4::identity();
+    for i in 0..4 {
+        for j in 0..4 {
+            matrix[(i, j)] = array[[i, j]];
+        }
+    }
+    Ok(matrix)
+}
+
+/// Parse an axis name ("x", "y" or "z", case insensitive).
+fn to_axis(name: &str) -> PyResult<Axis> {
+    match name.to_ascii_lowercase().as_str() {
+        "x" => Ok(Axis::X),
+        "y" => Ok(Axis::Y),
+        "z" => Ok(Axis::Z),
+        _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unknown axis '{}', expected 'x', 'y' or 'z'",
+            name
+        ))),
+    }
+}
+
+/// Convert a positive number of seconds into a duration.
+fn to_duration(seconds: f64) -> PyResult<Duration> {
+    if !(seconds > 0.0 && seconds.is_finite()) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "Duration must be a positive number of seconds",
+        ));
+    }
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+/// Convert a transform into a 4x4 numpy array.
+fn to_pyarray<'py>(py: Python<'py>, transform: &Transform) -> PyResult<&'py PyArray2<f32>> {
+    let rows: Vec<Vec<f32>> = transform.row_iter().map(|row| row.iter().copied().collect()).collect();
+    Ok(PyArray2::from_vec2(py, &rows)?)
 }
 
 #[pymodule]
 /// A fast OpenGL-based 6D frames viewer with Python bindings.
 fn frames_viewer(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyViewer>()?;
+    m.add_class::<PyGridPlane>()?;
     Ok(())
 } 
\ No newline at end of file
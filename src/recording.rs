@@ -0,0 +1,150 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+use crate::renderer::Renderer;
+use crate::{Result, ViewerError};
+
+/// Video being recorded by piping rendered images to `ffmpeg`.
+///
+/// Images are sampled at a fixed rate from whatever the render loop draws,
+/// repeating the last image when drawing is slower than the video.
+pub(crate) struct Recording {
+    path: PathBuf,
+    interval: f64,  // Seconds between two video frames
+    countdown: f64, // Seconds until the next video frame is due
+    encoder: Option<Encoder>,
+    error: Option<ViewerError>,
+}
+
+/// `ffmpeg` process encoding raw RGBA images of a fixed size.
+struct Encoder {
+    process: Child,
+    input: ChildStdin,
+    width: u32,
+    height: u32,
+}
+
+impl Recording {
+    pub fn new(path: PathBuf, fps: f32) -> Result<Self> {
+        if !(fps.is_finite() && fps > 0.0) {
+            return Err(ViewerError::RecordingError(format!("invalid frame rate {}", fps)));
+        }
+        Ok(Recording {
+            path,
+            interval: 1.0 / fps as f64,
+            countdown: 0.0,
+            encoder: None,
+            error: None,
+        })
+    }
+
+    /// Record what `renderer` last drew, `dt` seconds after the previous image.
+    ///
+    /// A failure stops the recording; it is reported by [`finish`](Self::finish).
+    pub fn capture(&mut self, renderer: &Renderer, dt: f64) {
+        if self.error.is_some() {
+            return;
+        }
+        let due = self.frames_due(dt);
+        if due == 0 {
+            return;
+        }
+        let (width, height, pixels) = renderer.read_pixels();
+        if let Err(e) = self.write(width, height, &pixels, due) {
+            log::error!("Recording to {} failed: {}", self.path.display(), e);
+            self.error = Some(e);
+        }
+    }
+
+    /// Number of video frames to emit after `dt` more seconds.
+    fn frames_due(&mut self, dt: f64) -> usize {
+        self.countdown -= dt;
+        let mut due = 0;
+        while self.countdown <= 0.0 {
+            due += 1;
+            self.countdown += self.interval;
+        }
+        due
+    }
+
+    fn write(&mut self, width: u32, height: u32, pixels: &[u8], count: usize) -> Result<()> {
+        if self.encoder.is_none() {
+            // The video gets the size of the first recorded image
+            self.encoder = Some(Encoder::spawn(&self.path, width, height, 1.0 / self.interval)?);
+        }
+        let encoder = self.encoder.as_mut().expect("encoder was just started");
+        if (width, height) != (encoder.width, encoder.height) {
+            return Err(ViewerError::RecordingError("the render size changed while recording".to_string()));
+        }
+        for _ in 0..count {
+            encoder.input.write_all(pixels)?;
+        }
+        Ok(())
+    }
+
+    /// Close the video file, waiting for `ffmpeg` to finish encoding it.
+    pub fn finish(self) -> Result<()> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let Some(Encoder { mut process, input, .. }) = self.encoder else {
+            return Err(ViewerError::RecordingError("no image was rendered while recording".to_string()));
+        };
+        // Closing the input ends the video
+        drop(input);
+        let status = process.wait()?;
+        if !status.success() {
+            return Err(ViewerError::RecordingError(format!("ffmpeg exited with {}", status)));
+        }
+        Ok(())
+    }
+}
+
+impl Encoder {
+    /// Start `ffmpeg`, choosing the container and codec from the extension of `path`.
+    fn spawn(path: &Path, width: u32, height: u32, fps: f64) -> Result<Self> {
+        let mut command = Command::new("ffmpeg");
+        command
+            .args(["-y", "-loglevel", "error", "-f", "rawvideo", "-pixel_format", "rgba"])
+            .args(["-video_size", &format!("{}x{}", width, height)])
+            .args(["-framerate", &fps.to_string(), "-i", "-"]);
+        let is_gif = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("gif"));
+        if !is_gif {
+            // Widely playable H.264 needs 4:2:0 chroma, hence even dimensions
+            command.args(["-pix_fmt", "yuv420p", "-vf", "pad=ceil(iw/2)*2:ceil(ih/2)*2"]);
+        }
+        let mut process = command
+            .arg(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .map_err(|e| ViewerError::RecordingError(format!("could not run ffmpeg: {}", e)))?;
+        let input = process.stdin.take().expect("ffmpeg input is piped");
+        Ok(Encoder {
+            process,
+            input,
+            width,
+            height,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_due() {
+        let mut recording = Recording::new(PathBuf::from("video.mp4"), 10.0).unwrap();
+        // The first drawn image starts the video
+        assert_eq!(recording.frames_due(0.01), 1);
+        // Drawing faster than the video skips images
+        assert_eq!(recording.frames_due(0.05), 0);
+        assert_eq!(recording.frames_due(0.05), 1);
+        // Drawing slower repeats them
+        assert_eq!(recording.frames_due(0.3), 3);
+
+        assert!(Recording::new(PathBuf::from("video.mp4"), 0.0).is_err());
+    }
+}
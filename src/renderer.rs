@@ -1,25 +1,162 @@
 use gl::types::*;
 use nalgebra as na;
+use std::collections::HashMap;
 use std::ffi::CString;
 use std::mem;
 use std::ptr;
+use std::time::Instant;
 
 use crate::camera::Camera;
+use crate::config::{GridConfig, GridStyle, ViewerConfig};
+use crate::font::{self, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::{Result, ViewerError};
 
 pub struct Renderer {
     program: GLuint,
     frame_vao: GLuint,
     frame_vbo: GLuint,
+    arrow_vao: GLuint, // Cones at the tips of the frame axes
+    arrow_vbo: GLuint,
+    axis_length: f32, // Of the axes currently in the frame and arrow buffers
     grid_vao: GLuint,
     grid_vbo: GLuint,
-    camera: Camera,
+    overlay_vao: GLuint,
+    overlay_vbo: GLuint,
+    quad_vao: GLuint,
+    quad_vbo: GLuint,
+    grid_vertex_count: GLsizei,
+    grid_visible: bool,
+    grid_line_width: f32,
+    grid: GridConfig, // Layout of the world grid currently in its buffer
+    clear_color: [f32; 4],
+    width: u32,
+    height: u32,
+    render_scale: f32,
+    offscreen: bool,
+    embedded: bool,
+    target_framebuffer: GLuint, // Framebuffer presented to, bound by the host when embedded
+    msaa_samples: GLsizei,      // Of the offscreen render target
+    framebuffer: Option<Framebuffer>,
+    resolve_framebuffer: Option<Framebuffer>, // Single-sampled copy of a multisampled render target
+    present_framebuffer: Option<Framebuffer>, // Downscaled image, when the target is multisampled
+    pick_program: GLuint, // Draws flat ID colors for picking
+    pick_uniforms: PickUniforms,
+    pick_framebuffer: Option<Framebuffer>, // At window resolution, created on the first pick
+    trajectories: HashMap<String, LineBuffer>,
+    grids: HashMap<String, AnchoredGrid>,
+    geometries: HashMap<(Primitive, String), Geometry>,
+    meshes: HashMap<String, MeshBuffer>,
+    sphere: LineBuffer, // Unit sphere wireframe, scaled into ellipsoids
+    world_up: [f32; 3],
+    depth_test: bool,
     uniform_locations: UniformLocations,
 }
 
+/// Growable GPU buffer of line strip vertices.
+struct LineBuffer {
+    vao: GLuint,
+    vbo: GLuint,
+    capacity: usize, // In vertices
+    len: usize,      // In vertices
+}
+
+/// Grid attached to a transform other than the world origin.
+struct AnchoredGrid {
+    transform: na::Matrix4<f32>,
+    config: GridConfig,
+    buffer: LineBuffer,
+}
+
+/// How the vertices of a geometry are assembled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Primitive {
+    /// One point per vertex
+    Points,
+    /// One segment per pair of vertices
+    Lines,
+    /// A polyline through all vertices
+    LineStrip,
+}
+
+/// Point cloud or line segments, uploaded again whenever it is replaced.
+struct Geometry {
+    buffer: LineBuffer,
+    updated: Instant, // When the uploaded vertices were pushed
+}
+
+/// Indexed triangle mesh, uploaded again whenever it is replaced.
+struct MeshBuffer {
+    vao: GLuint,
+    vbo: GLuint,
+    ebo: GLuint,
+    index_count: GLsizei,
+    updated: Instant, // When the uploaded mesh was attached
+}
+
+/// Offscreen render target with a color and a depth attachment.
+struct Framebuffer {
+    fbo: GLuint,
+    color: GLuint,
+    depth: GLuint,
+    width: GLsizei,
+    height: GLsizei,
+    samples: GLsizei, // 0 when not multisampled
+}
+
 struct UniformLocations {
     model: GLint,
     view: GLint,
     projection: GLint,
+    alpha: GLint,
+    use_color: GLint,
+    color: GLint,
+    dash_period: GLint,
+}
+
+struct PickUniforms {
+    model: GLint,
+    view: GLint,
+    projection: GLint,
+    id: GLint,
+}
+
+/// Frame drawn into the ID buffer when picking.
+pub struct PickTarget<'a> {
+    /// Model transform of the axes, as given to [`Renderer::render`]
+    pub axes: na::Matrix4<f32>,
+    /// Width of the axis lines in pixels
+    pub line_width: f32,
+    /// Drawn over everything else, ignoring depth
+    pub on_top: bool,
+    /// Name and transform of the mesh attached to the frame, if any
+    pub mesh: Option<(&'a str, na::Matrix4<f32>)>,
+}
+
+/// Appearance of a single frame's axes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AxesStyle {
+    /// Opacity multiplier
+    pub alpha: f32,
+    /// Colors of the X, Y and Z axes, replacing the default red/green/blue
+    pub colors: Option<[[f32; 4]; 3]>,
+    /// Width of the axis lines in pixels
+    pub line_width: f32,
+    /// Draw over everything rendered before, ignoring depth
+    pub on_top: bool,
+    /// Draw the axis lines dashed, e.g. for a frame whose pose is stale
+    pub dashed: bool,
+}
+
+impl Default for AxesStyle {
+    fn default() -> Self {
+        AxesStyle {
+            alpha: 1.0,
+            colors: None,
+            line_width: 3.0,
+            on_top: false,
+            dashed: false,
+        }
+    }
 }
 
 const VERTEX_SHADER: &str = r#"
@@ -32,58 +169,147 @@ const VERTEX_SHADER: &str = r#"
     uniform mat4 projection;
     
     out vec4 fragColor;
+    out vec3 localPosition;
     
     void main() {
         gl_Position = projection * view * model * vec4(position, 1.0);
         fragColor = color;
+        localPosition = position;
     }
 "#;
 
 const FRAGMENT_SHADER: &str = r#"
     #version 330 core
     in vec4 fragColor;
+    in vec3 localPosition;
     out vec4 FragColor;
     
+    uniform float alpha;
+    uniform bool useColor;
+    uniform vec4 color;
+    uniform float dashPeriod; // Model space length of a dash and its gap, 0 for solid lines
+    
     void main() {
-        FragColor = fragColor;
+        // Lines through the model origin, like frame axes, are cut into dashes
+        if (dashPeriod > 0.0 && fract(length(localPosition) / dashPeriod) > 0.5) {
+            discard;
+        }
+        vec4 baseColor = useColor ? color : fragColor;
+        FragColor = vec4(baseColor.rgb, baseColor.a * alpha);
     }
 "#;
 
-const GRID_SIZE: f32 = 1.0; // 1 meter
-const GRID_STEP: f32 = 0.1; // 10 centimeters
-const GRID_LINES: i32 = (GRID_SIZE / GRID_STEP) as i32;
-const VERTICES_PER_GRID: i32 = (GRID_LINES + 1) * 4; // 2 lines (horizontal/vertical) * 2 vertices per line
-const TOTAL_GRID_VERTICES: i32 = VERTICES_PER_GRID * 3; // 3 grids (XY, XZ, YZ)
+const PICK_VERTEX_SHADER: &str = r#"
+    #version 330 core
+    layout (location = 0) in vec3 position;
+    
+    uniform mat4 model;
+    uniform mat4 view;
+    uniform mat4 projection;
+    
+    void main() {
+        gl_Position = projection * view * model * vec4(position, 1.0);
+    }
+"#;
+
+const PICK_FRAGMENT_SHADER: &str = r#"
+    #version 330 core
+    out vec4 FragColor;
+    
+    uniform vec4 id; // Encoded by pick_color
+    
+    void main() {
+        FragColor = id;
+    }
+"#;
+
+pub const FLOATS_PER_VERTEX: usize = 7; // Position (3) + color (4)
+pub const AXIS_LENGTH: f32 = 0.1; // Length of the frame axes in meters
+const TRAJECTORY_COLOR: [f32; 4] = [0.3, 0.3, 0.3, 1.0];
+const TRAJECTORY_INITIAL_CAPACITY: usize = 256; // Vertices
+const DASH_LENGTH: f32 = 0.02; // 2 centimeters
+const DASH_GAP: f32 = 0.02;
+const AXIS_DASHES: f32 = 4.0; // Along each dashed axis
+const SPHERE_SEGMENTS: usize = 36; // Per circle
+const SPHERE_CIRCLES: usize = 6; // Of each kind, meridians and latitude rings
+const ARROW_LENGTH: f32 = 0.02; // Of the cone ending each axis, in meters before scaling
+const ARROW_RADIUS: f32 = 0.006;
+const ARROW_SEGMENTS: usize = 12;
+const ARROW_VERTICES: usize = ARROW_SEGMENTS * 6; // Per axis: side and base triangles
+const MARKER_SIZE: f32 = 8.0; // Frame origin markers, in pixels
+const SCALE_BAR_MARGIN: f32 = 20.0; // Pixels from the window corner
+const SCALE_BAR_COLOR: [f32; 4] = [0.2, 0.2, 0.2, 1.0];
+const GNOMON_MARGIN: f32 = 20.0; // Pixels from the window corner to the end of the axes
+const GNOMON_LENGTH: f32 = 40.0; // Of each axis, in pixels
+const GNOMON_LABEL_SIZE: f32 = 10.0;
 
 impl Renderer {
-    pub fn new() -> Self {
+    /// Create a renderer in a GL context owned by a host application.
+    ///
+    /// Function pointers are loaded through `loader`, and each frame is drawn
+    /// into whichever framebuffer the host has bound when it starts.
+    ///
+    /// # Safety
+    ///
+    /// An OpenGL 3.3 context must be current on the calling thread.
+    pub unsafe fn new_in_current_context(
+        config: &ViewerConfig,
+        loader: impl FnMut(&'static str) -> *const std::ffi::c_void,
+    ) -> Result<Self> {
+        gl::load_with(loader);
+        let mut renderer = Renderer::new(config)?;
+        renderer.embedded = true;
+        Ok(renderer)
+    }
+
+    /// Create a renderer in the current GL context, failing if its shaders do
+    /// not compile or link.
+    pub fn new(config: &ViewerConfig) -> Result<Self> {
         unsafe {
-            gl::Enable(gl::DEPTH_TEST);
-            gl::Enable(gl::BLEND);
-            gl::Enable(gl::LINE_SMOOTH);
-            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            let state = &config.render_state;
+            set_capability(gl::DEPTH_TEST, state.depth_test);
+            set_capability(gl::BLEND, state.blend);
+            set_capability(gl::LINE_SMOOTH, state.line_smooth);
+            set_capability(gl::MULTISAMPLE, config.msaa_samples > 0);
+            gl::BlendFunc(state.blend_func[0], state.blend_func[1]);
             gl::LineWidth(1.0);
-            gl::DepthFunc(gl::LEQUAL);
-            
-            // Create and compile shaders
-            let vertex_shader = compile_shader(VERTEX_SHADER, gl::VERTEX_SHADER);
-            let fragment_shader = compile_shader(FRAGMENT_SHADER, gl::FRAGMENT_SHADER);
+            gl::DepthFunc(state.depth_func);
+            let mut max_samples = 0;
+            gl::GetIntegerv(gl::MAX_SAMPLES, &mut max_samples);
             
-            // Create program
-            let program = gl::CreateProgram();
-            gl::AttachShader(program, vertex_shader);
-            gl::AttachShader(program, fragment_shader);
-            gl::LinkProgram(program);
+            let program = create_program(VERTEX_SHADER, FRAGMENT_SHADER).map_err(ViewerError::ShaderError)?;
+            let pick_program = match create_program(PICK_VERTEX_SHADER, PICK_FRAGMENT_SHADER) {
+                Ok(pick_program) => pick_program,
+                Err(e) => {
+                    gl::DeleteProgram(program);
+                    return Err(ViewerError::ShaderError(e));
+                }
+            };
             
             // Get uniform locations
             let model = CString::new("model").unwrap();
             let view = CString::new("view").unwrap();
             let projection = CString::new("projection").unwrap();
+            let alpha = CString::new("alpha").unwrap();
+            let use_color = CString::new("useColor").unwrap();
+            let color = CString::new("color").unwrap();
+            let dash_period = CString::new("dashPeriod").unwrap();
             
             let uniform_locations = UniformLocations {
                 model: gl::GetUniformLocation(program, model.as_ptr()),
                 view: gl::GetUniformLocation(program, view.as_ptr()),
                 projection: gl::GetUniformLocation(program, projection.as_ptr()),
+                alpha: gl::GetUniformLocation(program, alpha.as_ptr()),
+                use_color: gl::GetUniformLocation(program, use_color.as_ptr()),
+                color: gl::GetUniformLocation(program, color.as_ptr()),
+                dash_period: gl::GetUniformLocation(program, dash_period.as_ptr()),
+            };
+            let id = CString::new("id").unwrap();
+            let pick_uniforms = PickUniforms {
+                model: gl::GetUniformLocation(pick_program, model.as_ptr()),
+                view: gl::GetUniformLocation(pick_program, view.as_ptr()),
+                projection: gl::GetUniformLocation(pick_program, projection.as_ptr()),
+                id: gl::GetUniformLocation(pick_program, id.as_ptr()),
             };
             
             // Create VAO and VBO for coordinate frames
@@ -92,17 +318,7 @@ impl Renderer {
             gl::GenVertexArrays(1, &mut frame_vao);
             gl::GenBuffers(1, &mut frame_vbo);
             
-            // Vertices for coordinate axes (position and color)
-            #[rustfmt::skip]
-            let frame_vertices: [f32; 42] = [
-                // Position           // Color (RGBA)
-                0.0, 0.0, 0.0,       1.0, 0.0, 0.0, 1.0,  // X axis start
-                0.1, 0.0, 0.0,       1.0, 0.0, 0.0, 1.0,  // X axis end
-                0.0, 0.0, 0.0,       0.0, 1.0, 0.0, 1.0,  // Y axis start
-                0.0, 0.1, 0.0,       0.0, 1.0, 0.0, 1.0,  // Y axis end
-                0.0, 0.0, 0.0,       0.0, 0.0, 1.0, 1.0,  // Z axis start
-                0.0, 0.0, 0.1,       0.0, 0.0, 1.0, 1.0,  // Z axis end
-            ];
+            let frame_vertices = frame_vertices(config.axis_length);
             
             gl::BindVertexArray(frame_vao);
             gl::BindBuffer(gl::ARRAY_BUFFER, frame_vbo);
@@ -115,64 +331,29 @@ impl Renderer {
             
             setup_vertex_attributes();
 
+            // Arrowheads, drawn with the same model transform as the axes
+            let arrow_vertices = arrow_vertices(config.axis_length);
+            let mut arrow_vao = 0;
+            let mut arrow_vbo = 0;
+            gl::GenVertexArrays(1, &mut arrow_vao);
+            gl::GenBuffers(1, &mut arrow_vbo);
+            gl::BindVertexArray(arrow_vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, arrow_vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                (arrow_vertices.len() * mem::size_of::<f32>()) as GLsizeiptr,
+                arrow_vertices.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+            setup_vertex_attributes();
+
             // Create VAO and VBO for grid
             let mut grid_vao = 0;
             let mut grid_vbo = 0;
             gl::GenVertexArrays(1, &mut grid_vao);
             gl::GenBuffers(1, &mut grid_vbo);
 
-            // Generate grid vertices for all three planes
-            let mut grid_vertices = Vec::new();
-
-            // Colors for each plane's grid
-            let xy_color = [0.8, 0.8, 0.8, 0.3]; // Light gray with transparency
-            let xz_color = [0.8, 0.8, 0.8, 0.3];
-            let yz_color = [0.8, 0.8, 0.8, 0.3];
-
-            // XY plane grid (parallel to ground)
-            for i in 0..=GRID_LINES {
-                let pos = i as f32 * GRID_STEP;
-                // Lines parallel to X axis
-                grid_vertices.extend_from_slice(&[
-                    0.0, pos, 0.0, xy_color[0], xy_color[1], xy_color[2], xy_color[3],
-                    GRID_SIZE, pos, 0.0, xy_color[0], xy_color[1], xy_color[2], xy_color[3],
-                ]);
-                // Lines parallel to Y axis
-                grid_vertices.extend_from_slice(&[
-                    pos, 0.0, 0.0, xy_color[0], xy_color[1], xy_color[2], xy_color[3],
-                    pos, GRID_SIZE, 0.0, xy_color[0], xy_color[1], xy_color[2], xy_color[3],
-                ]);
-            }
-
-            // YZ plane grid (back wall)
-            for i in 0..=GRID_LINES {
-                let pos = i as f32 * GRID_STEP;
-                // Lines parallel to Y axis
-                grid_vertices.extend_from_slice(&[
-                    0.0, pos, 0.0, yz_color[0], yz_color[1], yz_color[2], yz_color[3],
-                    0.0, pos, GRID_SIZE, yz_color[0], yz_color[1], yz_color[2], yz_color[3],
-                ]);
-                // Lines parallel to Z axis
-                grid_vertices.extend_from_slice(&[
-                    0.0, 0.0, pos, yz_color[0], yz_color[1], yz_color[2], yz_color[3],
-                    0.0, GRID_SIZE, pos, yz_color[0], yz_color[1], yz_color[2], yz_color[3],
-                ]);
-            }
-
-            // XZ plane grid (side wall)
-            for i in 0..=GRID_LINES {
-                let pos = i as f32 * GRID_STEP;
-                // Lines parallel to X axis
-                grid_vertices.extend_from_slice(&[
-                    0.0, 0.0, pos, xz_color[0], xz_color[1], xz_color[2], xz_color[3],
-                    GRID_SIZE, 0.0, pos, xz_color[0], xz_color[1], xz_color[2], xz_color[3],
-                ]);
-                // Lines parallel to Z axis
-                grid_vertices.extend_from_slice(&[
-                    pos, 0.0, 0.0, xz_color[0], xz_color[1], xz_color[2], xz_color[3],
-                    pos, 0.0, GRID_SIZE, xz_color[0], xz_color[1], xz_color[2], xz_color[3],
-                ]);
-            }
+            let grid_vertices = grid_vertices(&config.grid, config.world_up);
 
             gl::BindVertexArray(grid_vao);
             gl::BindBuffer(gl::ARRAY_BUFFER, grid_vbo);
@@ -184,64 +365,1262 @@ impl Renderer {
             );
 
             setup_vertex_attributes();
+
+            // Create VAO and VBO for a unit quad in the XY plane, centered on the origin
+            let mut quad_vao = 0;
+            let mut quad_vbo = 0;
+            gl::GenVertexArrays(1, &mut quad_vao);
+            gl::GenBuffers(1, &mut quad_vbo);
+
+            #[rustfmt::skip]
+            let quad_vertices: [f32; 28] = [
+                // Position          // Color, replaced by the color uniform
+                -0.5, -0.5, 0.0,     1.0, 1.0, 1.0, 1.0,
+                 0.5, -0.5, 0.0,     1.0, 1.0, 1.0, 1.0,
+                 0.5,  0.5, 0.0,     1.0, 1.0, 1.0, 1.0,
+                -0.5,  0.5, 0.0,     1.0, 1.0, 1.0, 1.0,
+            ];
+
+            gl::BindVertexArray(quad_vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, quad_vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                mem::size_of_val(&quad_vertices) as GLsizeiptr,
+                quad_vertices.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+
+            setup_vertex_attributes();
+
+            // Create VAO and VBO for screen-space overlays, filled on every draw
+            let mut overlay_vao = 0;
+            let mut overlay_vbo = 0;
+            gl::GenVertexArrays(1, &mut overlay_vao);
+            gl::GenBuffers(1, &mut overlay_vbo);
+            gl::BindVertexArray(overlay_vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, overlay_vbo);
+            setup_vertex_attributes();
+
+            let sphere_vertices = sphere_vertices();
+            let mut sphere = LineBuffer::new(sphere_vertices.len() / FLOATS_PER_VERTEX);
+            sphere.append(&sphere_vertices);
             
-            // Clean up shaders
-            gl::DeleteShader(vertex_shader);
-            gl::DeleteShader(fragment_shader);
-            
-            Renderer {
+            Ok(Renderer {
                 program,
                 frame_vao,
                 frame_vbo,
+                arrow_vao,
+                arrow_vbo,
+                axis_length: config.axis_length,
                 grid_vao,
                 grid_vbo,
-                camera: Camera::new(800.0 / 600.0),
+                overlay_vao,
+                overlay_vbo,
+                quad_vao,
+                quad_vbo,
+                grid_vertex_count: (grid_vertices.len() / FLOATS_PER_VERTEX) as GLsizei,
+                grid_visible: config.grid.visible,
+                grid_line_width: config.grid_line_width,
+                grid: config.grid.clone(),
+                clear_color: config.background,
+                width: config.width,
+                height: config.height,
+                render_scale: config.render_scale,
+                offscreen: false,
+                embedded: false,
+                target_framebuffer: 0,
+                msaa_samples: max_samples.min(config.msaa_samples as GLint),
+                framebuffer: None,
+                resolve_framebuffer: None,
+                present_framebuffer: None,
+                pick_program,
+                pick_uniforms,
+                pick_framebuffer: None,
+                trajectories: HashMap::new(),
+                grids: HashMap::new(),
+                geometries: HashMap::new(),
+                meshes: HashMap::new(),
+                sphere,
+                world_up: config.world_up,
+                depth_test: config.render_state.depth_test,
                 uniform_locations,
-            }
+            })
         }
     }
     
-    pub fn render(&self, transform: &na::Matrix4<f32>) {
+    pub fn render(&self, camera: &Camera, transform: &na::Matrix4<f32>, style: &AxesStyle) {
         unsafe {
             gl::UseProgram(self.program);
 
-            let view = self.camera.view_matrix();
-            let projection = self.camera.projection_matrix();
+            let view = camera.view_matrix();
+            let projection = camera.projection_matrix();
             
-            // Draw grid first
-            gl::LineWidth(1.0); // Thin lines for grid
-            gl::UniformMatrix4fv(self.uniform_locations.model, 1, gl::FALSE, na::Matrix4::identity().as_ptr());
             gl::UniformMatrix4fv(self.uniform_locations.view, 1, gl::FALSE, view.as_ptr());
             gl::UniformMatrix4fv(self.uniform_locations.projection, 1, gl::FALSE, projection.as_ptr());
+            gl::Uniform1f(self.uniform_locations.alpha, 1.0);
+            gl::Uniform1i(self.uniform_locations.use_color, 0);
+
+            // Draw grid first
+            if self.grid_visible {
+                gl::LineWidth(self.grid_line_width * self.render_scale); // Thin lines for grid
+                gl::UniformMatrix4fv(self.uniform_locations.model, 1, gl::FALSE, na::Matrix4::identity().as_ptr());
+
+                gl::BindVertexArray(self.grid_vao);
+                gl::DrawArrays(gl::LINES, 0, self.grid_vertex_count);
+            }
             
-            gl::BindVertexArray(self.grid_vao);
-            gl::DrawArrays(gl::LINES, 0, TOTAL_GRID_VERTICES);
-            
-            // Draw coordinate frame with thicker lines and ensure it's on top
-            gl::LineWidth(3.0);
+            // Draw coordinate frame with thicker lines
+            if style.on_top {
+                gl::Disable(gl::DEPTH_TEST);
+            }
+            gl::LineWidth(style.line_width * self.render_scale);
             gl::UniformMatrix4fv(self.uniform_locations.model, 1, gl::FALSE, transform.as_ptr());
+            gl::Uniform1f(self.uniform_locations.alpha, style.alpha);
+            // Only the lines are dashed, the arrow heads stay whole
+            let dash_period = if style.dashed { self.axis_length / AXIS_DASHES } else { 0.0 };
             
-            gl::BindVertexArray(self.frame_vao);
-            gl::DrawArrays(gl::LINES, 0, 6);
+            match &style.colors {
+                Some(colors) => {
+                    // One draw per axis, each with its own color
+                    gl::Uniform1i(self.uniform_locations.use_color, 1);
+                    for (axis, color) in colors.iter().enumerate() {
+                        gl::Uniform4fv(self.uniform_locations.color, 1, color.as_ptr());
+                        gl::Uniform1f(self.uniform_locations.dash_period, dash_period);
+                        gl::BindVertexArray(self.frame_vao);
+                        gl::DrawArrays(gl::LINES, 2 * axis as GLint, 2);
+                        gl::Uniform1f(self.uniform_locations.dash_period, 0.0);
+                        gl::BindVertexArray(self.arrow_vao);
+                        gl::DrawArrays(gl::TRIANGLES, (axis * ARROW_VERTICES) as GLint, ARROW_VERTICES as GLsizei);
+                    }
+                    gl::Uniform1i(self.uniform_locations.use_color, 0);
+                }
+                None => {
+                    gl::Uniform1f(self.uniform_locations.dash_period, dash_period);
+                    gl::BindVertexArray(self.frame_vao);
+                    gl::DrawArrays(gl::LINES, 0, 6);
+                    gl::Uniform1f(self.uniform_locations.dash_period, 0.0);
+                    gl::BindVertexArray(self.arrow_vao);
+                    gl::DrawArrays(gl::TRIANGLES, 0, (3 * ARROW_VERTICES) as GLsizei);
+                }
+            }
+            if style.on_top {
+                set_capability(gl::DEPTH_TEST, self.depth_test);
+            }
+        }
+    }
+
+    /// Upload the points of a trajectory that are not on the GPU yet.
+    ///
+    /// Only the new tail of `points` is uploaded, so a trajectory growing by a
+    /// point per tick costs O(1) per tick rather than O(n).
+    pub fn sync_trajectory(&mut self, name: &str, points: &[[f32; 3]]) {
+        let buffer = self
+            .trajectories
+            .entry(name.to_string())
+            .or_insert_with(|| unsafe { LineBuffer::new(TRAJECTORY_INITIAL_CAPACITY) });
+
+        if points.len() < buffer.len {
+            buffer.len = 0;
+        }
+        if points.len() == buffer.len {
+            return;
+        }
+
+        let mut vertices = Vec::with_capacity((points.len() - buffer.len) * FLOATS_PER_VERTEX);
+        for point in &points[buffer.len..] {
+            vertices.extend_from_slice(point);
+            vertices.extend_from_slice(&TRAJECTORY_COLOR);
+        }
+        unsafe { buffer.append(&vertices) };
+    }
+
+    /// Release the GPU buffers of trajectories for which `keep` returns false.
+    pub fn retain_trajectories(&mut self, keep: impl Fn(&str) -> bool) {
+        self.trajectories.retain(|name, buffer| {
+            let retained = keep(name);
+            if !retained {
+                unsafe { buffer.delete() };
+            }
+            retained
+        });
+    }
+
+    pub fn render_trajectories(&self, camera: &Camera) {
+        let identity = na::Matrix4::<f32>::identity();
+
+        unsafe {
+            gl::UseProgram(self.program);
+            gl::LineWidth(2.0 * self.render_scale);
+
+            gl::UniformMatrix4fv(self.uniform_locations.model, 1, gl::FALSE, identity.as_ptr());
+            gl::UniformMatrix4fv(self.uniform_locations.view, 1, gl::FALSE, camera.view_matrix().as_ptr());
+            gl::UniformMatrix4fv(self.uniform_locations.projection, 1, gl::FALSE, camera.projection_matrix().as_ptr());
+            gl::Uniform1f(self.uniform_locations.alpha, 1.0);
+            gl::Uniform1i(self.uniform_locations.use_color, 0);
+
+            for buffer in self.trajectories.values() {
+                gl::BindVertexArray(buffer.vao);
+                gl::DrawArrays(gl::LINE_STRIP, 0, buffer.len as GLsizei);
+            }
+        }
+    }
+
+    /// Update an anchored grid, regenerating its vertices if its configuration changed.
+    pub fn sync_grid(&mut self, name: &str, transform: &na::Matrix4<f32>, config: &GridConfig) {
+        if let Some(grid) = self.grids.get_mut(name) {
+            grid.transform = *transform;
+            if grid.config == *config {
+                return;
+            }
+            unsafe { grid.buffer.delete() };
+        }
+
+        let vertices = grid_vertices(config, self.world_up);
+        let buffer = unsafe {
+            let mut buffer = LineBuffer::new(vertices.len() / FLOATS_PER_VERTEX);
+            buffer.append(&vertices);
+            buffer
+        };
+        self.grids.insert(
+            name.to_string(),
+            AnchoredGrid {
+                transform: *transform,
+                config: config.clone(),
+                buffer,
+            },
+        );
+    }
+
+    /// Release the GPU buffers of anchored grids for which `keep` returns false.
+    pub fn retain_grids(&mut self, keep: impl Fn(&str) -> bool) {
+        self.grids.retain(|name, grid| {
+            let retained = keep(name);
+            if !retained {
+                unsafe { grid.buffer.delete() };
+            }
+            retained
+        });
+    }
+
+    pub fn render_grids(&self, camera: &Camera) {
+        unsafe {
+            gl::UseProgram(self.program);
+            gl::LineWidth(self.grid_line_width * self.render_scale); // Like the world grid
+
+            gl::UniformMatrix4fv(self.uniform_locations.view, 1, gl::FALSE, camera.view_matrix().as_ptr());
+            gl::UniformMatrix4fv(self.uniform_locations.projection, 1, gl::FALSE, camera.projection_matrix().as_ptr());
+            gl::Uniform1f(self.uniform_locations.alpha, 1.0);
+            gl::Uniform1i(self.uniform_locations.use_color, 0);
+
+            for grid in self.grids.values().filter(|grid| grid.config.visible) {
+                gl::UniformMatrix4fv(self.uniform_locations.model, 1, gl::FALSE, grid.transform.as_ptr());
+                gl::BindVertexArray(grid.buffer.vao);
+                gl::DrawArrays(gl::LINES, 0, grid.buffer.len as GLsizei);
+            }
+        }
+    }
+
+    /// Upload the vertices (position and color) of a geometry if they were
+    /// pushed after the ones currently on the GPU.
+    pub fn sync_geometry(&mut self, primitive: Primitive, name: &str, vertices: &[f32], updated: Instant) {
+        let geometry = self
+            .geometries
+            .entry((primitive, name.to_string()))
+            .or_insert_with(|| Geometry {
+                buffer: unsafe { LineBuffer::new(vertices.len() / FLOATS_PER_VERTEX) },
+                updated,
+            });
+        if geometry.updated == updated && geometry.buffer.len > 0 {
+            return;
+        }
+
+        geometry.updated = updated;
+        geometry.buffer.len = 0;
+        unsafe { geometry.buffer.append(vertices) };
+    }
+
+    /// Release the GPU buffers of geometries for which `keep` returns false.
+    pub fn retain_geometries(&mut self, keep: impl Fn(Primitive, &str) -> bool) {
+        self.geometries.retain(|(primitive, name), geometry| {
+            let retained = keep(*primitive, name);
+            if !retained {
+                unsafe { geometry.buffer.delete() };
+            }
+            retained
+        });
+    }
+
+    pub fn render_geometries(&self, camera: &Camera) {
+        let identity = na::Matrix4::<f32>::identity();
+
+        unsafe {
+            gl::UseProgram(self.program);
+            gl::LineWidth(self.render_scale);
+            gl::PointSize(3.0 * self.render_scale);
+
+            gl::UniformMatrix4fv(self.uniform_locations.model, 1, gl::FALSE, identity.as_ptr());
+            gl::UniformMatrix4fv(self.uniform_locations.view, 1, gl::FALSE, camera.view_matrix().as_ptr());
+            gl::UniformMatrix4fv(self.uniform_locations.projection, 1, gl::FALSE, camera.projection_matrix().as_ptr());
+            gl::Uniform1f(self.uniform_locations.alpha, 1.0);
+            gl::Uniform1i(self.uniform_locations.use_color, 0);
+
+            for ((primitive, _), geometry) in &self.geometries {
+                let mode = match primitive {
+                    Primitive::Points => gl::POINTS,
+                    Primitive::Lines => gl::LINES,
+                    Primitive::LineStrip => gl::LINE_STRIP,
+                };
+                gl::BindVertexArray(geometry.buffer.vao);
+                gl::DrawArrays(mode, 0, geometry.buffer.len as GLsizei);
+            }
+        }
+    }
+
+    /// Upload the vertices (position and color) and triangle indices of the mesh
+    /// attached to a frame if they were attached after the ones on the GPU.
+    pub fn sync_mesh(&mut self, name: &str, vertices: &[f32], indices: &[u32], updated: Instant) {
+        if self.meshes.get(name).is_some_and(|mesh| mesh.updated == updated) {
+            return;
+        }
+
+        let mesh = self.meshes.entry(name.to_string()).or_insert_with(|| unsafe { MeshBuffer::new(updated) });
+        mesh.updated = updated;
+        unsafe { mesh.upload(vertices, indices) };
+    }
+
+    /// Release the GPU buffers of meshes for which `keep` returns false.
+    pub fn retain_meshes(&mut self, keep: impl Fn(&str) -> bool) {
+        self.meshes.retain(|name, mesh| {
+            let retained = keep(name);
+            if !retained {
+                unsafe { mesh.delete() };
+            }
+            retained
+        });
+    }
+
+    /// Draw the mesh attached to the frame `name` with the frame's transform.
+    pub fn render_mesh(&self, camera: &Camera, name: &str, transform: &na::Matrix4<f32>, alpha: f32) {
+        let Some(mesh) = self.meshes.get(name) else {
+            return;
+        };
+
+        unsafe {
+            gl::UseProgram(self.program);
+            gl::UniformMatrix4fv(self.uniform_locations.model, 1, gl::FALSE, transform.as_ptr());
+            gl::UniformMatrix4fv(self.uniform_locations.view, 1, gl::FALSE, camera.view_matrix().as_ptr());
+            gl::UniformMatrix4fv(self.uniform_locations.projection, 1, gl::FALSE, camera.projection_matrix().as_ptr());
+            gl::Uniform1f(self.uniform_locations.alpha, alpha);
+            gl::Uniform1i(self.uniform_locations.use_color, 0);
+
+            gl::BindVertexArray(mesh.vao);
+            gl::DrawElements(gl::TRIANGLES, mesh.index_count, gl::UNSIGNED_INT, ptr::null());
+        }
+    }
+
+    /// Draw a filled, usually translucent, `width` x `height` rectangle centered
+    /// in the XY plane of `transform`.
+    ///
+    /// Depth writes are off while drawing, so what lies behind stays visible.
+    pub fn render_plane(&self, camera: &Camera, transform: &na::Matrix4<f32>, width: f32, height: f32, color: [f32; 4]) {
+        let model = transform * na::Matrix4::new_nonuniform_scaling(&na::Vector3::new(width, height, 1.0));
+
+        unsafe {
+            gl::UseProgram(self.program);
+
+            gl::UniformMatrix4fv(self.uniform_locations.model, 1, gl::FALSE, model.as_ptr());
+            gl::UniformMatrix4fv(self.uniform_locations.view, 1, gl::FALSE, camera.view_matrix().as_ptr());
+            gl::UniformMatrix4fv(self.uniform_locations.projection, 1, gl::FALSE, camera.projection_matrix().as_ptr());
+            gl::Uniform1f(self.uniform_locations.alpha, 1.0);
+            gl::Uniform1i(self.uniform_locations.use_color, 1);
+            gl::Uniform4fv(self.uniform_locations.color, 1, color.as_ptr());
+
+            gl::DepthMask(gl::FALSE);
+            gl::BindVertexArray(self.quad_vao);
+            gl::DrawArrays(gl::TRIANGLE_FAN, 0, 4);
+            gl::DepthMask(gl::TRUE);
+            gl::Uniform1i(self.uniform_locations.use_color, 0);
+        }
+    }
+
+    /// Draw a wireframe ellipsoid, the unit sphere mapped through `transform`.
+    pub fn render_ellipsoid(&self, camera: &Camera, transform: &na::Matrix4<f32>, color: [f32; 4]) {
+        unsafe {
+            gl::UseProgram(self.program);
+            gl::LineWidth(self.render_scale);
+
+            gl::UniformMatrix4fv(self.uniform_locations.model, 1, gl::FALSE, transform.as_ptr());
+            gl::UniformMatrix4fv(self.uniform_locations.view, 1, gl::FALSE, camera.view_matrix().as_ptr());
+            gl::UniformMatrix4fv(self.uniform_locations.projection, 1, gl::FALSE, camera.projection_matrix().as_ptr());
+            gl::Uniform1f(self.uniform_locations.alpha, 1.0);
+            gl::Uniform1i(self.uniform_locations.use_color, 1);
+            gl::Uniform4fv(self.uniform_locations.color, 1, color.as_ptr());
+
+            gl::BindVertexArray(self.sphere.vao);
+            gl::DrawArrays(gl::LINES, 0, self.sphere.len as GLsizei);
+            gl::Uniform1i(self.uniform_locations.use_color, 0);
+        }
+    }
+
+    /// Draw `text` facing the camera, with its bottom-left corner at the world `position`.
+    ///
+    /// `size` is the text height in window pixels. Nothing is drawn if the
+    /// position is behind the camera or off-screen.
+    pub fn render_text(&self, camera: &Camera, position: &na::Point3<f32>, text: &str, size: f32, color: [f32; 4]) {
+        let Some((x, y)) = camera.project(position, self.width, self.height) else {
+            return;
+        };
+        self.render_screen_text(x, y, text, size, color);
+    }
+
+    /// Draw `text` with its bottom-left corner at window pixel (`x`, `y`), origin at the top-left.
+    pub fn render_screen_text(&self, x: f32, y: f32, text: &str, size: f32, color: [f32; 4]) {
+        let y = self.height as f32 - y; // Overlay coordinates start at the bottom-left
+        let pixel = size / GLYPH_HEIGHT as f32;
+
+        // One horizontal line per run of lit pixels in each glyph row
+        let mut vertices = Vec::new();
+        for (index, c) in text.chars().enumerate() {
+            let columns = font::glyph(c);
+            let glyph_x = x + (index * (GLYPH_WIDTH + 1)) as f32 * pixel;
+            for row in 0..GLYPH_HEIGHT {
+                let row_y = y + (GLYPH_HEIGHT - row) as f32 * pixel - pixel / 2.0;
+                let mut column = 0;
+                while column < GLYPH_WIDTH {
+                    if columns[column] & (1 << row) == 0 {
+                        column += 1;
+                        continue;
+                    }
+                    let start = column;
+                    while column < GLYPH_WIDTH && columns[column] & (1 << row) != 0 {
+                        column += 1;
+                    }
+                    vertices.extend_from_slice(&[glyph_x + start as f32 * pixel, row_y, 0.0]);
+                    vertices.extend_from_slice(&color);
+                    vertices.extend_from_slice(&[glyph_x + column as f32 * pixel, row_y, 0.0]);
+                    vertices.extend_from_slice(&color);
+                }
+            }
+        }
+        self.render_overlay_lines(&vertices, pixel);
+    }
+
+    /// Draw a scale bar showing the on-screen length of 1 meter at the camera target.
+    ///
+    /// Minor ticks mark every 10 centimeters.
+    pub fn render_scale_bar(&self, camera: &Camera) {
+        let view_projection = camera.projection_matrix() * camera.view_matrix();
+        let start = camera.target();
+        let end = start + camera.right();
+
+        let project = |point: na::Point3<f32>| {
+            let clip = view_projection * point.to_homogeneous();
+            na::Vector2::new(clip.x / clip.w, clip.y / clip.w)
+        };
+        let ndc_length = (project(end) - project(start)).magnitude();
+        let length = (ndc_length * self.width as f32 / 2.0).min(self.width as f32 - 2.0 * SCALE_BAR_MARGIN);
+        if !length.is_finite() || length <= 0.0 {
+            return;
+        }
+
+        let (x, y) = (SCALE_BAR_MARGIN, SCALE_BAR_MARGIN);
+        let mut segments = vec![[x, y, x + length, y]];
+        for i in 0..=10 {
+            let tick_x = x + length * i as f32 / 10.0;
+            let tick_height = if i % 5 == 0 { 8.0 } else { 4.0 };
+            segments.push([tick_x, y, tick_x, y + tick_height]);
+        }
+
+        let mut vertices = Vec::with_capacity(segments.len() * 2 * FLOATS_PER_VERTEX);
+        for [x0, y0, x1, y1] in segments {
+            vertices.extend_from_slice(&[x0, y0, 0.0]);
+            vertices.extend_from_slice(&SCALE_BAR_COLOR);
+            vertices.extend_from_slice(&[x1, y1, 0.0]);
+            vertices.extend_from_slice(&SCALE_BAR_COLOR);
+        }
+        self.render_overlay_lines(&vertices, 2.0);
+    }
+
+    /// Draw an XYZ axis indicator in the bottom-right corner of the window.
+    ///
+    /// The axes turn with the camera but keep their on-screen position and size,
+    /// as seen through an orthographic projection.
+    pub fn render_gnomon(&self, camera: &Camera) {
+        let view = camera.view_matrix();
+        let center_x = self.width as f32 - GNOMON_MARGIN - GNOMON_LENGTH;
+        let center_y = GNOMON_MARGIN + GNOMON_LENGTH;
+        let axes = [("X", [1.0, 0.0, 0.0, 1.0]), ("Y", [0.0, 1.0, 0.0, 1.0]), ("Z", [0.0, 0.0, 1.0, 1.0])];
+
+        let mut vertices = Vec::with_capacity(axes.len() * 2 * FLOATS_PER_VERTEX);
+        let mut labels = Vec::with_capacity(axes.len());
+        for (axis, (name, color)) in axes.into_iter().enumerate() {
+            // Dropping the depth of the rotated axis projects it orthographically
+            let direction = view.transform_vector(&na::Vector3::ith(axis, GNOMON_LENGTH));
+            vertices.extend_from_slice(&[center_x, center_y, 0.0]);
+            vertices.extend_from_slice(&color);
+            vertices.extend_from_slice(&[center_x + direction.x, center_y + direction.y, 0.0]);
+            vertices.extend_from_slice(&color);
+
+            // Just past the tip, centered on the axis
+            let x = center_x + direction.x * 1.2 - GNOMON_LABEL_SIZE / 3.0;
+            let y = center_y + direction.y * 1.2 - GNOMON_LABEL_SIZE / 2.0;
+            labels.push((x, y, name, color));
+        }
+        self.render_overlay_lines(&vertices, 2.0);
+        for (x, y, name, color) in labels {
+            self.render_screen_text(x, self.height as f32 - y, name, GNOMON_LABEL_SIZE, color);
+        }
+    }
+
+    /// Draw line vertices given in window pixels (origin at the bottom-left) on top of the scene.
+    fn render_overlay_lines(&self, vertices: &[f32], line_width: f32) {
+        let projection = na::Matrix4::new_orthographic(0.0, self.width as f32, 0.0, self.height as f32, -1.0, 1.0);
+
+        unsafe {
+            gl::Disable(gl::DEPTH_TEST);
+            self.draw_dynamic(gl::LINES, vertices, &na::Matrix4::identity(), &projection, line_width);
+            set_capability(gl::DEPTH_TEST, self.depth_test);
+        }
+    }
+
+    /// Draw dashed world-space line segments, e.g. to hint at relations between points.
+    pub fn render_dashed_lines(&self, camera: &Camera, segments: &[(na::Point3<f32>, na::Point3<f32>)], color: [f32; 4]) {
+        let mut vertices = Vec::new();
+        for (start, end) in segments {
+            dashed_line_vertices(&mut vertices, *start, *end, color, DASH_LENGTH, DASH_GAP);
+        }
+        self.draw_dynamic(gl::LINES, &vertices, &camera.view_matrix(), &camera.projection_matrix(), 1.0);
+    }
+
+    /// Draw world-space dots of `MARKER_SIZE` pixels, e.g. at frame origins.
+    pub fn render_markers(&self, camera: &Camera, markers: &[(na::Point3<f32>, [f32; 4])]) {
+        let mut vertices = Vec::with_capacity(markers.len() * FLOATS_PER_VERTEX);
+        for (position, color) in markers {
+            vertices.extend_from_slice(position.coords.as_slice());
+            vertices.extend_from_slice(color);
+        }
+        self.draw_dynamic(gl::POINTS, &vertices, &camera.view_matrix(), &camera.projection_matrix(), MARKER_SIZE);
+    }
+
+    /// Upload vertices to the shared dynamic buffer and draw them as `mode`
+    /// primitives, with lines `size` pixels wide or points `size` pixels across.
+    fn draw_dynamic(&self, mode: GLenum, vertices: &[f32], view: &na::Matrix4<f32>, projection: &na::Matrix4<f32>, size: f32) {
+        let identity = na::Matrix4::<f32>::identity();
+
+        unsafe {
+            gl::UseProgram(self.program);
+            gl::LineWidth(size * self.render_scale);
+            gl::PointSize(size * self.render_scale);
+
+            gl::UniformMatrix4fv(self.uniform_locations.model, 1, gl::FALSE, identity.as_ptr());
+            gl::UniformMatrix4fv(self.uniform_locations.view, 1, gl::FALSE, view.as_ptr());
+            gl::UniformMatrix4fv(self.uniform_locations.projection, 1, gl::FALSE, projection.as_ptr());
+            gl::Uniform1f(self.uniform_locations.alpha, 1.0);
+            gl::Uniform1i(self.uniform_locations.use_color, 0);
+
+            gl::BindVertexArray(self.overlay_vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.overlay_vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                mem::size_of_val(vertices) as GLsizeiptr,
+                vertices.as_ptr() as *const _,
+                gl::DYNAMIC_DRAW,
+            );
+            gl::DrawArrays(mode, 0, (vertices.len() / FLOATS_PER_VERTEX) as GLsizei);
+        }
+    }
+
+    pub fn set_grid_visible(&mut self, visible: bool) {
+        self.grid_visible = visible;
+    }
+
+    /// Width of the world and anchored grid lines in pixels.
+    pub fn set_grid_line_width(&mut self, width: f32) {
+        self.grid_line_width = width;
+    }
+
+    /// Regenerate the frame axes and their arrowheads if their length in meters changed.
+    pub fn set_axis_length(&mut self, length: f32) {
+        if self.axis_length == length {
+            return;
+        }
+        self.axis_length = length;
+        let frame_vertices = frame_vertices(length);
+        let arrow_vertices = arrow_vertices(length);
+        unsafe {
+            for (vbo, vertices) in [(self.frame_vbo, &frame_vertices[..]), (self.arrow_vbo, &arrow_vertices[..])] {
+                gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+                gl::BufferData(
+                    gl::ARRAY_BUFFER,
+                    mem::size_of_val(vertices) as GLsizeiptr,
+                    vertices.as_ptr() as *const _,
+                    gl::STATIC_DRAW,
+                );
+            }
+        }
+    }
+
+    /// Regenerate the world grid if its size, step or mode changed.
+    pub fn set_grid(&mut self, grid: &GridConfig) {
+        if self.grid == *grid {
+            return;
+        }
+        self.grid = grid.clone();
+        self.upload_grid();
+    }
+
+    /// Change the world up direction, regenerating the grids drawn on the ground plane.
+    pub fn set_world_up(&mut self, up: [f32; 3]) {
+        if self.world_up == up {
+            return;
+        }
+        self.world_up = up;
+        self.upload_grid();
+
+        // Anchored grids are regenerated at their next sync
+        for grid in self.grids.values() {
+            unsafe { grid.buffer.delete() };
+        }
+        self.grids.clear();
+    }
+
+    fn upload_grid(&mut self) {
+        let vertices = grid_vertices(&self.grid, self.world_up);
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.grid_vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                mem::size_of_val(vertices.as_slice()) as GLsizeiptr,
+                vertices.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+        }
+        self.grid_vertex_count = (vertices.len() / FLOATS_PER_VERTEX) as GLsizei;
+    }
+
+    pub fn set_background(&mut self, color: [f32; 4]) {
+        self.clear_color = color;
+    }
+
+    /// Render at `scale` times the window resolution and downsample when presenting.
+    ///
+    /// A scale above 1 supersamples away aliasing on lines.
+    pub fn set_render_scale(&mut self, scale: f32) {
+        self.render_scale = scale;
+    }
+
+    /// Always render into an offscreen framebuffer that is never presented.
+    ///
+    /// Used when there is no window (or default framebuffer) to draw to.
+    pub fn set_offscreen(&mut self, offscreen: bool) {
+        self.offscreen = offscreen;
+    }
+
+    /// Bind the render target for this tick and clear it.
+    pub fn begin_frame(&mut self) {
+        let width = ((self.width as f32 * self.render_scale).round() as GLsizei).max(1);
+        let height = ((self.height as f32 * self.render_scale).round() as GLsizei).max(1);
+
+        unsafe {
+            if self.embedded {
+                let mut binding = 0;
+                gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut binding);
+                self.target_framebuffer = binding as GLuint;
+            }
+
+            let fbo = if self.render_scale == 1.0 && !self.offscreen {
+                for slot in [&mut self.framebuffer, &mut self.resolve_framebuffer, &mut self.present_framebuffer] {
+                    if let Some(framebuffer) = slot.take() {
+                        framebuffer.delete();
+                    }
+                }
+                self.target_framebuffer
+            } else {
+                // Multisampled like the window, whose own samples it replaces
+                Framebuffer::ensure(&mut self.framebuffer, width, height, self.msaa_samples).fbo
+            };
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+            gl::Viewport(0, 0, width, height);
         }
+        self.clear();
+    }
+
+    /// Resolve the offscreen render target (if any) into the window.
+    ///
+    /// Blits from or into a multisampled framebuffer cannot scale, so a
+    /// multisampled render target is first resolved at its own size, and a
+    /// scaled image is downsampled into a single-sampled buffer before being
+    /// copied into a multisampled window.
+    pub fn end_frame(&mut self) {
+        let Some(framebuffer) = &self.framebuffer else {
+            return;
+        };
+        let size = (framebuffer.width, framebuffer.height);
+        let window_size = (self.width.max(1) as GLsizei, self.height.max(1) as GLsizei);
+        unsafe {
+            let mut source = framebuffer.fbo;
+            if framebuffer.samples > 0 {
+                let resolve = Framebuffer::ensure(&mut self.resolve_framebuffer, size.0, size.1, 0);
+                blit(source, resolve.fbo, size, size, gl::NEAREST);
+                source = resolve.fbo;
+            }
+
+            if !self.offscreen {
+                gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.target_framebuffer);
+                let mut target_samples = 0;
+                gl::GetIntegerv(gl::SAMPLE_BUFFERS, &mut target_samples);
+                if target_samples > 0 && size != window_size {
+                    let present = Framebuffer::ensure(&mut self.present_framebuffer, window_size.0, window_size.1, 0);
+                    blit(source, present.fbo, size, window_size, gl::LINEAR);
+                    blit(present.fbo, self.target_framebuffer, window_size, window_size, gl::NEAREST);
+                } else {
+                    blit(source, self.target_framebuffer, size, window_size, gl::LINEAR);
+                }
+                gl::Viewport(0, 0, window_size.0, window_size.1);
+            }
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.target_framebuffer);
+        }
+    }
+
+    /// Index in `targets` of the frame drawn closest to window pixel (`x`, `y`)
+    /// (origin at the top-left), within `radius` pixels of it.
+    ///
+    /// The frames are drawn offscreen, each in a color encoding its index, with
+    /// their meshes hiding what is behind them as on screen, and the pixels
+    /// around the cursor are read back. The render target of the next frame is
+    /// unaffected.
+    pub fn pick(&mut self, camera: &Camera, targets: &[PickTarget], x: f32, y: f32, radius: i32) -> Option<usize> {
+        let (width, height) = (self.width.max(1) as GLsizei, self.height.max(1) as GLsizei);
+        // OpenGL rows start at the bottom of the image
+        let (cx, cy) = (x.floor() as GLsizei, height - 1 - y.floor() as GLsizei);
+        let (x0, y0) = ((cx - radius).max(0), (cy - radius).max(0));
+        let (x1, y1) = ((cx + radius).min(width - 1), (cy + radius).min(height - 1));
+        if x0 > x1 || y0 > y1 {
+            return None;
+        }
+
+        unsafe {
+            let fbo = Framebuffer::ensure(&mut self.pick_framebuffer, width, height, 0).fbo;
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+            gl::Viewport(0, 0, width, height);
+
+            // Anything blending or smoothing colors would corrupt the IDs
+            let blend = gl::IsEnabled(gl::BLEND) == gl::TRUE;
+            let line_smooth = gl::IsEnabled(gl::LINE_SMOOTH) == gl::TRUE;
+            let multisample = gl::IsEnabled(gl::MULTISAMPLE) == gl::TRUE;
+            gl::Disable(gl::BLEND);
+            gl::Disable(gl::LINE_SMOOTH);
+            gl::Disable(gl::MULTISAMPLE);
+            gl::Enable(gl::DEPTH_TEST);
+            gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+
+            gl::UseProgram(self.pick_program);
+            gl::UniformMatrix4fv(self.pick_uniforms.view, 1, gl::FALSE, camera.view_matrix().as_ptr());
+            gl::UniformMatrix4fv(self.pick_uniforms.projection, 1, gl::FALSE, camera.projection_matrix().as_ptr());
+
+            // Meshes first, so that they hide the axes behind them, then frames
+            // drawn over everything else with the depth test off
+            for (index, target) in targets.iter().enumerate() {
+                if let Some((name, transform)) = target.mesh {
+                    if let Some(mesh) = self.meshes.get(name) {
+                        gl::Uniform4fv(self.pick_uniforms.id, 1, pick_color(index as u32 + 1).as_ptr());
+                        gl::UniformMatrix4fv(self.pick_uniforms.model, 1, gl::FALSE, transform.as_ptr());
+                        gl::BindVertexArray(mesh.vao);
+                        gl::DrawElements(gl::TRIANGLES, mesh.index_count, gl::UNSIGNED_INT, ptr::null());
+                    }
+                }
+            }
+            for on_top in [false, true] {
+                if on_top {
+                    gl::Disable(gl::DEPTH_TEST);
+                }
+                for (index, target) in targets.iter().enumerate().filter(|(_, target)| target.on_top == on_top) {
+                    gl::Uniform4fv(self.pick_uniforms.id, 1, pick_color(index as u32 + 1).as_ptr());
+                    gl::UniformMatrix4fv(self.pick_uniforms.model, 1, gl::FALSE, target.axes.as_ptr());
+                    gl::LineWidth(target.line_width);
+                    gl::BindVertexArray(self.frame_vao);
+                    gl::DrawArrays(gl::LINES, 0, 6);
+                    gl::BindVertexArray(self.arrow_vao);
+                    gl::DrawArrays(gl::TRIANGLES, 0, (3 * ARROW_VERTICES) as GLsizei);
+                }
+            }
+
+            let (region_width, region_height) = (x1 - x0 + 1, y1 - y0 + 1);
+            let mut pixels = vec![0u8; region_width as usize * region_height as usize * 4];
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(x0, y0, region_width, region_height, gl::RGBA, gl::UNSIGNED_BYTE, pixels.as_mut_ptr() as *mut _);
+
+            set_capability(gl::DEPTH_TEST, self.depth_test);
+            set_capability(gl::BLEND, blend);
+            set_capability(gl::LINE_SMOOTH, line_smooth);
+            set_capability(gl::MULTISAMPLE, multisample);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.target_framebuffer);
+            gl::Viewport(0, 0, width, height);
+
+            let id = closest_id(&pixels, region_width as usize, (cx - x0, cy - y0), radius)?;
+            Some(id as usize - 1)
+        }
+    }
+
+    /// Read back the last rendered image as RGBA rows, top row first.
+    ///
+    /// Reads the offscreen framebuffer when there is one, so supersampled
+    /// renders come back at their full resolution.
+    pub fn read_pixels(&self) -> (u32, u32, Vec<u8>) {
+        // Multisampled framebuffers cannot be read directly, only their resolved copy
+        let offscreen = self.framebuffer.as_ref().map(|fb| match &self.resolve_framebuffer {
+            Some(resolve) if fb.samples > 0 => resolve,
+            _ => fb,
+        });
+        let (fbo, width, height) = match offscreen {
+            Some(framebuffer) => (framebuffer.fbo, framebuffer.width, framebuffer.height),
+            None => (self.target_framebuffer, self.width as GLsizei, self.height as GLsizei),
+        };
+
+        let mut pixels = vec![0u8; width as usize * height as usize * 4];
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, fbo);
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(0, 0, width, height, gl::RGBA, gl::UNSIGNED_BYTE, pixels.as_mut_ptr() as *mut _);
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.target_framebuffer);
+        }
+
+        // OpenGL rows start at the bottom of the image
+        let flipped = pixels.chunks_exact(width as usize * 4).rev().flatten().copied().collect();
+        (width as u32, height as u32, flipped)
     }
 
     pub fn clear(&self) {
         unsafe {
-            gl::ClearColor(0.95, 0.95, 0.95, 1.0); // Light gray background
+            let [r, g, b, a] = self.clear_color;
+            gl::ClearColor(r, g, b, a);
             gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
         }
     }
 
     pub fn resize(&mut self, width: u32, height: u32) {
-        self.camera.set_aspect(width as f32 / height as f32);
+        self.width = width;
+        self.height = height;
         unsafe {
             gl::Viewport(0, 0, width as i32, height as i32);
         }
     }
+}
+
+/// Copy the color of framebuffer `read`, `from` pixels in size, onto `to` pixels of `draw`.
+unsafe fn blit(read: GLuint, draw: GLuint, from: (GLsizei, GLsizei), to: (GLsizei, GLsizei), filter: GLenum) {
+    gl::BindFramebuffer(gl::READ_FRAMEBUFFER, read);
+    gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, draw);
+    gl::BlitFramebuffer(0, 0, from.0, from.1, 0, 0, to.0, to.1, gl::COLOR_BUFFER_BIT, filter);
+}
 
-    pub fn camera_mut(&mut self) -> &mut Camera {
-        &mut self.camera
+/// Color drawing a nonzero pick `id` into an RGBA8 buffer, its low byte in red.
+fn pick_color(id: u32) -> [f32; 4] {
+    let byte = |shift: u32| ((id >> shift) & 0xff) as f32 / 255.0;
+    [byte(0), byte(8), byte(16), 1.0]
+}
+
+/// Nonzero pick ID closest to `center` within `radius` pixels, in RGBA rows
+/// of `width` pixels.
+fn closest_id(pixels: &[u8], width: usize, center: (i32, i32), radius: i32) -> Option<u32> {
+    pixels
+        .chunks_exact(4)
+        .enumerate()
+        .filter_map(|(index, pixel)| {
+            let id = pixel[0] as u32 | (pixel[1] as u32) << 8 | (pixel[2] as u32) << 16;
+            let (dx, dy) = ((index % width) as i32 - center.0, (index / width) as i32 - center.1);
+            let distance = dx * dx + dy * dy;
+            (id != 0 && distance <= radius * radius).then_some((distance, id))
+        })
+        .min()
+        .map(|(_, id)| id)
+}
+
+unsafe fn set_capability(capability: GLenum, enabled: bool) {
+    if enabled {
+        gl::Enable(capability);
+    } else {
+        gl::Disable(capability);
+    }
+}
+
+/// GL_LINES vertices of a unit sphere wireframe: meridians and latitude rings.
+fn sphere_vertices() -> Vec<f32> {
+    use std::f32::consts::PI;
+
+    let mut points = Vec::new();
+    for circle in 0..SPHERE_CIRCLES {
+        let longitude = circle as f32 * PI / SPHERE_CIRCLES as f32;
+        let latitude = (circle as f32 + 0.5) * PI / SPHERE_CIRCLES as f32 - PI / 2.0;
+        for segment in 0..SPHERE_SEGMENTS {
+            let angles = [segment, segment + 1].map(|i| i as f32 * 2.0 * PI / SPHERE_SEGMENTS as f32);
+            // Meridian through the poles
+            points.extend(angles.map(|a| [a.sin() * longitude.cos(), a.sin() * longitude.sin(), a.cos()]));
+            // Ring of constant latitude
+            points.extend(angles.map(|a| [latitude.cos() * a.cos(), latitude.cos() * a.sin(), latitude.sin()]));
+        }
+    }
+
+    let mut vertices = Vec::with_capacity(points.len() * FLOATS_PER_VERTEX);
+    for point in points {
+        vertices.extend_from_slice(&point);
+        vertices.extend_from_slice(&[1.0; 4]); // Replaced by the color uniform
+    }
+    vertices
+}
+
+/// GL_LINES vertices of the X, Y and Z axes of a frame, `length` meters long,
+/// in red, green and blue.
+fn frame_vertices(length: f32) -> [f32; 42] {
+    #[rustfmt::skip]
+    let vertices = [
+        // Position           // Color (RGBA)
+        0.0, 0.0, 0.0,        1.0, 0.0, 0.0, 1.0,  // X axis start
+        length, 0.0, 0.0,     1.0, 0.0, 0.0, 1.0,  // X axis end
+        0.0, 0.0, 0.0,        0.0, 1.0, 0.0, 1.0,  // Y axis start
+        0.0, length, 0.0,     0.0, 1.0, 0.0, 1.0,  // Y axis end
+        0.0, 0.0, 0.0,        0.0, 0.0, 1.0, 1.0,  // Z axis start
+        0.0, 0.0, length,     0.0, 0.0, 1.0, 1.0,  // Z axis end
+    ];
+    vertices
+}
+
+/// GL_TRIANGLES vertices of a closed cone at the tip of each frame axis, in the
+/// axis color, ARROW_VERTICES per axis in X, Y, Z order.
+fn arrow_vertices(axis_length: f32) -> Vec<f32> {
+    use std::f32::consts::PI;
+
+    // Arrowheads keep their size, up to half of shorter axes
+    let base = axis_length - ARROW_LENGTH.min(axis_length / 2.0);
+    let mut vertices = Vec::with_capacity(3 * ARROW_VERTICES * FLOATS_PER_VERTEX);
+    for axis in 0..3 {
+        let mut color = [0.0, 0.0, 0.0, 1.0];
+        color[axis] = 1.0;
+        // Point given as (along the axis, across, across), rotated onto the axis
+        let mut push = |[along, u, v]: [f32; 3]| {
+            let mut position = [0.0; 3];
+            position[axis] = along;
+            position[(axis + 1) % 3] = u;
+            position[(axis + 2) % 3] = v;
+            vertices.extend_from_slice(&position);
+            vertices.extend_from_slice(&color);
+        };
+        for segment in 0..ARROW_SEGMENTS {
+            let [a, b] = [segment, segment + 1].map(|i| i as f32 * 2.0 * PI / ARROW_SEGMENTS as f32);
+            let rim_a = [base, ARROW_RADIUS * a.cos(), ARROW_RADIUS * a.sin()];
+            let rim_b = [base, ARROW_RADIUS * b.cos(), ARROW_RADIUS * b.sin()];
+            for point in [[axis_length, 0.0, 0.0], rim_a, rim_b, [base, 0.0, 0.0], rim_b, rim_a] {
+                push(point);
+            }
+        }
+    }
+    vertices
+}
+
+/// Append GL_LINES vertices for a dashed line from `start` to `end`.
+fn dashed_line_vertices(
+    vertices: &mut Vec<f32>,
+    start: na::Point3<f32>,
+    end: na::Point3<f32>,
+    color: [f32; 4],
+    dash: f32,
+    gap: f32,
+) {
+    let length = (end - start).magnitude();
+    if length == 0.0 {
+        return;
+    }
+    let direction = (end - start) / length;
+
+    let mut distance = 0.0;
+    while distance < length {
+        let dash_start = start + direction * distance;
+        let dash_end = start + direction * (distance + dash).min(length);
+        vertices.extend_from_slice(dash_start.coords.as_slice());
+        vertices.extend_from_slice(&color);
+        vertices.extend_from_slice(dash_end.coords.as_slice());
+        vertices.extend_from_slice(&color);
+        distance += dash + gap;
+    }
+}
+
+/// Frees the GL objects, so the context the renderer was created in must still
+/// be alive and current when it is dropped. The render loops drop the renderer
+/// before their context, and embedding hosts keep their context current.
+impl Drop for Renderer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteProgram(self.program);
+            for vao in [self.frame_vao, self.arrow_vao, self.grid_vao, self.overlay_vao, self.quad_vao] {
+                gl::DeleteVertexArrays(1, &vao);
+            }
+            for vbo in [self.frame_vbo, self.arrow_vbo, self.grid_vbo, self.overlay_vbo, self.quad_vbo] {
+                gl::DeleteBuffers(1, &vbo);
+            }
+            for buffer in self.trajectories.values() {
+                buffer.delete();
+            }
+            for grid in self.grids.values() {
+                grid.buffer.delete();
+            }
+            for geometry in self.geometries.values() {
+                geometry.buffer.delete();
+            }
+            for mesh in self.meshes.values() {
+                mesh.delete();
+            }
+            self.sphere.delete();
+            for slot in [&mut self.framebuffer, &mut self.resolve_framebuffer, &mut self.present_framebuffer] {
+                if let Some(framebuffer) = slot.take() {
+                    framebuffer.delete();
+                }
+            }
+            gl::DeleteProgram(self.pick_program);
+            if let Some(framebuffer) = self.pick_framebuffer.take() {
+                framebuffer.delete();
+            }
+        }
+    }
+}
+
+impl LineBuffer {
+    unsafe fn new(capacity: usize) -> Self {
+        let mut vao = 0;
+        let mut vbo = 0;
+        gl::GenVertexArrays(1, &mut vao);
+        gl::GenBuffers(1, &mut vbo);
+        gl::BindVertexArray(vao);
+        gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+        gl::BufferData(gl::ARRAY_BUFFER, vertex_bytes(capacity), ptr::null(), gl::DYNAMIC_DRAW);
+        setup_vertex_attributes();
+
+        LineBuffer {
+            vao,
+            vbo,
+            capacity,
+            len: 0,
+        }
+    }
+
+    /// Append vertices, doubling the capacity when the buffer is full.
+    unsafe fn append(&mut self, vertices: &[f32]) {
+        let count = vertices.len() / FLOATS_PER_VERTEX;
+
+        if self.len + count > self.capacity {
+            let capacity = (self.capacity * 2).max(self.len + count);
+
+            // Copy the existing vertices into a larger buffer on the GPU
+            let mut vbo = 0;
+            gl::GenBuffers(1, &mut vbo);
+            gl::BindBuffer(gl::COPY_WRITE_BUFFER, vbo);
+            gl::BufferData(gl::COPY_WRITE_BUFFER, vertex_bytes(capacity), ptr::null(), gl::DYNAMIC_DRAW);
+            gl::BindBuffer(gl::COPY_READ_BUFFER, self.vbo);
+            gl::CopyBufferSubData(gl::COPY_READ_BUFFER, gl::COPY_WRITE_BUFFER, 0, 0, vertex_bytes(self.len));
+            gl::DeleteBuffers(1, &self.vbo);
+
+            self.vbo = vbo;
+            self.capacity = capacity;
+
+            gl::BindVertexArray(self.vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+            setup_vertex_attributes();
+        }
+
+        gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+        gl::BufferSubData(
+            gl::ARRAY_BUFFER,
+            vertex_bytes(self.len),
+            mem::size_of_val(vertices) as GLsizeiptr,
+            vertices.as_ptr() as *const _,
+        );
+        self.len += count;
+    }
+
+    unsafe fn delete(&self) {
+        gl::DeleteVertexArrays(1, &self.vao);
+        gl::DeleteBuffers(1, &self.vbo);
+    }
+}
+
+/// Size in bytes of `count` vertices.
+fn vertex_bytes(count: usize) -> GLsizeiptr {
+    (count * FLOATS_PER_VERTEX * mem::size_of::<f32>()) as GLsizeiptr
+}
+
+impl MeshBuffer {
+    unsafe fn new(updated: Instant) -> Self {
+        let mut mesh = MeshBuffer {
+            vao: 0,
+            vbo: 0,
+            ebo: 0,
+            index_count: 0,
+            updated,
+        };
+        gl::GenVertexArrays(1, &mut mesh.vao);
+        gl::GenBuffers(1, &mut mesh.vbo);
+        gl::GenBuffers(1, &mut mesh.ebo);
+
+        gl::BindVertexArray(mesh.vao);
+        gl::BindBuffer(gl::ARRAY_BUFFER, mesh.vbo);
+        setup_vertex_attributes();
+        // The element buffer binding is part of the VAO state
+        gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, mesh.ebo);
+        mesh
+    }
+
+    /// Replace the mesh with new vertices and triangle indices.
+    unsafe fn upload(&mut self, vertices: &[f32], indices: &[u32]) {
+        gl::BindVertexArray(self.vao);
+        gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+        gl::BufferData(
+            gl::ARRAY_BUFFER,
+            mem::size_of_val(vertices) as GLsizeiptr,
+            vertices.as_ptr() as *const _,
+            gl::STATIC_DRAW,
+        );
+        gl::BufferData(
+            gl::ELEMENT_ARRAY_BUFFER,
+            mem::size_of_val(indices) as GLsizeiptr,
+            indices.as_ptr() as *const _,
+            gl::STATIC_DRAW,
+        );
+        self.index_count = indices.len() as GLsizei;
+    }
+
+    unsafe fn delete(&self) {
+        gl::DeleteVertexArrays(1, &self.vao);
+        gl::DeleteBuffers(1, &self.vbo);
+        gl::DeleteBuffers(1, &self.ebo);
+    }
+}
+
+impl Framebuffer {
+    unsafe fn new(width: GLsizei, height: GLsizei, samples: GLsizei) -> Self {
+        let mut fbo = 0;
+        let mut color = 0;
+        let mut depth = 0;
+        gl::GenFramebuffers(1, &mut fbo);
+        gl::GenRenderbuffers(1, &mut color);
+        gl::GenRenderbuffers(1, &mut depth);
+
+        gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+
+        gl::BindRenderbuffer(gl::RENDERBUFFER, color);
+        gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, samples, gl::RGBA8, width, height);
+        gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::RENDERBUFFER, color);
+
+        gl::BindRenderbuffer(gl::RENDERBUFFER, depth);
+        gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, samples, gl::DEPTH_COMPONENT24, width, height);
+        gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::RENDERBUFFER, depth);
+
+        if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
+            log::error!("Offscreen framebuffer of {}x{} is incomplete", width, height);
+        }
+
+        gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+
+        Framebuffer {
+            fbo,
+            color,
+            depth,
+            width,
+            height,
+            samples,
+        }
+    }
+
+    /// The framebuffer in `slot`, created again if it has another size or sample count.
+    unsafe fn ensure(slot: &mut Option<Framebuffer>, width: GLsizei, height: GLsizei, samples: GLsizei) -> &Framebuffer {
+        if slot.as_ref().is_some_and(|fb| (fb.width, fb.height, fb.samples) != (width, height, samples)) {
+            if let Some(framebuffer) = slot.take() {
+                framebuffer.delete();
+            }
+        }
+        slot.get_or_insert_with(|| Framebuffer::new(width, height, samples))
+    }
+
+    unsafe fn delete(self) {
+        gl::DeleteFramebuffers(1, &self.fbo);
+        gl::DeleteRenderbuffers(1, &self.color);
+        gl::DeleteRenderbuffers(1, &self.depth);
+    }
+}
+
+/// Generate line vertices (position + color) for the XY, YZ and XZ plane grids.
+fn grid_vertices(grid: &GridConfig, world_up: [f32; 3]) -> Vec<f32> {
+    let lines = if grid.step > 0.0 { (grid.size / grid.step).round() as i32 } else { 0 };
+
+    // Planes to draw, by index of their normal axis
+    let planes = grid.planes(world_up);
+
+    let mut grid_vertices = Vec::new();
+
+    // Colors for each plane's grid
+    let xy_color = [0.8, 0.8, 0.8, 0.3]; // Light gray with transparency
+    let xz_color = [0.8, 0.8, 0.8, 0.3];
+    let yz_color = [0.8, 0.8, 0.8, 0.3];
+
+    // XY plane grid (parallel to ground)
+    for i in (0..=lines).filter(|_| planes[2]) {
+        let pos = i as f32 * grid.step;
+        // Lines parallel to X axis
+        grid_vertices.extend_from_slice(&[
+            0.0, pos, 0.0, xy_color[0], xy_color[1], xy_color[2], xy_color[3],
+            grid.size, pos, 0.0, xy_color[0], xy_color[1], xy_color[2], xy_color[3],
+        ]);
+        // Lines parallel to Y axis
+        grid_vertices.extend_from_slice(&[
+            pos, 0.0, 0.0, xy_color[0], xy_color[1], xy_color[2], xy_color[3],
+            pos, grid.size, 0.0, xy_color[0], xy_color[1], xy_color[2], xy_color[3],
+        ]);
+    }
+
+    // YZ plane grid (back wall)
+    for i in (0..=lines).filter(|_| planes[0]) {
+        let pos = i as f32 * grid.step;
+        // Lines parallel to Y axis
+        grid_vertices.extend_from_slice(&[
+            0.0, pos, 0.0, yz_color[0], yz_color[1], yz_color[2], yz_color[3],
+            0.0, pos, grid.size, yz_color[0], yz_color[1], yz_color[2], yz_color[3],
+        ]);
+        // Lines parallel to Z axis
+        grid_vertices.extend_from_slice(&[
+            0.0, 0.0, pos, yz_color[0], yz_color[1], yz_color[2], yz_color[3],
+            0.0, grid.size, pos, yz_color[0], yz_color[1], yz_color[2], yz_color[3],
+        ]);
+    }
+
+    // XZ plane grid (side wall)
+    for i in (0..=lines).filter(|_| planes[1]) {
+        let pos = i as f32 * grid.step;
+        // Lines parallel to X axis
+        grid_vertices.extend_from_slice(&[
+            0.0, 0.0, pos, xz_color[0], xz_color[1], xz_color[2], xz_color[3],
+            grid.size, 0.0, pos, xz_color[0], xz_color[1], xz_color[2], xz_color[3],
+        ]);
+        // Lines parallel to Z axis
+        grid_vertices.extend_from_slice(&[
+            pos, 0.0, 0.0, xz_color[0], xz_color[1], xz_color[2], xz_color[3],
+            pos, 0.0, grid.size, xz_color[0], xz_color[1], xz_color[2], xz_color[3],
+        ]);
+    }
+
+    match grid.style {
+        GridStyle::Solid => grid_vertices,
+        GridStyle::Dashed => {
+            // Dashes scale with the grid so that their number stays bounded
+            let dash = grid.step / 4.0;
+            let mut dashed_vertices = Vec::new();
+            for line in grid_vertices.chunks_exact(2 * FLOATS_PER_VERTEX) {
+                let start = na::Point3::new(line[0], line[1], line[2]);
+                let end = na::Point3::new(line[7], line[8], line[9]);
+                let color = [line[3], line[4], line[5], line[6]];
+                dashed_line_vertices(&mut dashed_vertices, start, end, color, dash, dash);
+            }
+            dashed_vertices
+        }
     }
 }
 
@@ -252,7 +1631,7 @@ unsafe fn setup_vertex_attributes() {
         3,
         gl::FLOAT,
         gl::FALSE,
-        7 * mem::size_of::<f32>() as GLsizei,
+        (FLOATS_PER_VERTEX * mem::size_of::<f32>()) as GLsizei,
         ptr::null(),
     );
     gl::EnableVertexAttribArray(0);
@@ -263,13 +1642,49 @@ unsafe fn setup_vertex_attributes() {
         4,
         gl::FLOAT,
         gl::FALSE,
-        7 * mem::size_of::<f32>() as GLsizei,
+        (FLOATS_PER_VERTEX * mem::size_of::<f32>()) as GLsizei,
         (3 * mem::size_of::<f32>()) as *const _,
     );
     gl::EnableVertexAttribArray(1);
 }
 
-fn compile_shader(source: &str, shader_type: GLenum) -> GLuint {
+/// Compile and link a shader program, returning the info log on failure.
+fn create_program(vertex_source: &str, fragment_source: &str) -> std::result::Result<GLuint, String> {
+    let vertex_shader = compile_shader(vertex_source, gl::VERTEX_SHADER)?;
+    let fragment_shader = match compile_shader(fragment_source, gl::FRAGMENT_SHADER) {
+        Ok(shader) => shader,
+        Err(e) => {
+            unsafe { gl::DeleteShader(vertex_shader) };
+            return Err(e);
+        }
+    };
+
+    unsafe {
+        let program = gl::CreateProgram();
+        gl::AttachShader(program, vertex_shader);
+        gl::AttachShader(program, fragment_shader);
+        gl::LinkProgram(program);
+
+        // The program keeps what it needs from the shaders once linked
+        gl::DeleteShader(vertex_shader);
+        gl::DeleteShader(fragment_shader);
+
+        let mut success = 0;
+        gl::GetProgramiv(program, gl::LINK_STATUS, &mut success);
+        if success == 0 {
+            let mut len = 0;
+            gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut len);
+            let mut buffer = vec![0u8; len.max(1) as usize];
+            gl::GetProgramInfoLog(program, len, ptr::null_mut(), buffer.as_mut_ptr() as *mut GLchar);
+            gl::DeleteProgram(program);
+            return Err(format!("Shader linking failed: {}", info_log(&buffer)));
+        }
+
+        Ok(program)
+    }
+}
+
+fn compile_shader(source: &str, shader_type: GLenum) -> std::result::Result<GLuint, String> {
     unsafe {
         let shader = gl::CreateShader(shader_type);
         let c_str = CString::new(source.as_bytes()).unwrap();
@@ -282,12 +1697,40 @@ fn compile_shader(source: &str, shader_type: GLenum) -> GLuint {
         if success == 0 {
             let mut len = 0;
             gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut len);
-            let mut buffer = Vec::with_capacity(len as usize);
-            buffer.set_len((len as usize) - 1);
+            let mut buffer = vec![0u8; len.max(1) as usize];
             gl::GetShaderInfoLog(shader, len, ptr::null_mut(), buffer.as_mut_ptr() as *mut GLchar);
-            panic!("Shader compilation failed: {}", String::from_utf8_lossy(&buffer));
+            gl::DeleteShader(shader);
+            return Err(format!("Shader compilation failed: {}", info_log(&buffer)));
         }
         
-        shader
+        Ok(shader)
+    }
+}
+
+/// Text of a NUL-terminated GL info log.
+fn info_log(buffer: &[u8]) -> String {
+    let end = buffer.iter().position(|&byte| byte == 0).unwrap_or(buffer.len());
+    String::from_utf8_lossy(&buffer[..end]).trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closest_id() {
+        // 3x3 pixels around the center, with IDs at two corners and one next to the center
+        let mut pixels = vec![0u8; 3 * 3 * 4];
+        let mut draw = |x: usize, y: usize, id: u32| {
+            let color = pick_color(id).map(|channel| (channel * 255.0).round() as u8);
+            pixels[(y * 3 + x) * 4..][..4].copy_from_slice(&color);
+        };
+        draw(0, 0, 70_000);
+        draw(2, 2, 2);
+        draw(1, 2, 300);
+
+        assert_eq!(closest_id(&pixels, 3, (1, 1), 1), Some(300));
+        assert_eq!(closest_id(&pixels, 3, (0, 0), 1), Some(70_000));
+        assert_eq!(closest_id(&pixels, 3, (1, 0), 0), None);
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,47 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+
+use crate::renderer::Renderer;
+use crate::Result;
+
+/// Request to save the next rendered image, answered once it is written.
+pub(crate) struct Screenshot {
+    pub path: PathBuf,
+    pub reply: Sender<Result<()>>,
+}
+
+impl Screenshot {
+    /// Read back what `renderer` last drew and save it, reporting the outcome to the requester.
+    pub fn save(self, renderer: &Renderer) {
+        let (width, height, pixels) = renderer.read_pixels();
+        let result = std::fs::File::create(&self.path)
+            .map_err(Into::into)
+            .and_then(|file| write_png(std::io::BufWriter::new(file), width, height, &pixels));
+        // The requester may have given up waiting
+        let _ = self.reply.send(result);
+    }
+}
+
+/// Encode RGBA rows, top row first, as a PNG image.
+pub fn write_png<W: Write>(writer: W, width: u32, height: u32, pixels: &[u8]) -> Result<()> {
+    let mut encoder = png::Encoder::new(writer, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(pixels)?;
+    writer.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_png() {
+        let mut output = Vec::new();
+        write_png(&mut output, 2, 1, &[255, 0, 0, 255, 0, 0, 255, 255]).unwrap();
+        assert_eq!(&output[..8], b"\x89PNG\r\n\x1a\n");
+    }
+}
@@ -0,0 +1,164 @@
+//! Rendering smoke test: renders a known scene offscreen and compares a
+//! thumbnail of the image against a stored reference.
+//!
+//! It needs an OpenGL driver with EGL (e.g. Mesa), so it is ignored by
+//! default: run it with `cargo test --features headless -- --ignored`, as the
+//! CI workflow does with Mesa's software renderer. The reference
+//! lives in `tests/reference/smoke_thumbnail.txt`; after an intended change,
+//! rewrite it by running the test with `UPDATE_REFERENCE=1`.
+
+use nalgebra as na;
+use std::fs;
+use std::path::Path;
+
+use crate::camera::Camera;
+use crate::config::ViewerConfig;
+use crate::headless::HeadlessContext;
+use crate::renderer::{AxesStyle, Renderer};
+use crate::Viewer;
+
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 120;
+const CELL: u32 = 10; // Thumbnail cell size in pixels
+const TOLERANCE: f32 = 4.0; // Mean absolute difference allowed, in gray levels
+const REFERENCE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/reference/smoke_thumbnail.txt");
+
+/// Render the reference scene and return its RGBA pixels.
+fn render_scene() -> Vec<u8> {
+    let context = HeadlessContext::new().expect("Failed to create a headless OpenGL context");
+    gl::load_with(|symbol| context.get_proc_address(symbol));
+
+    let config = ViewerConfig {
+        width: WIDTH,
+        height: HEIGHT,
+        ..ViewerConfig::default()
+    };
+    let mut renderer = Renderer::new(&config).expect("Failed to create the renderer");
+    renderer.set_offscreen(true);
+    draw_scene(&mut renderer);
+
+    let (width, height, pixels) = renderer.read_pixels();
+    assert_eq!((width, height), (WIDTH, HEIGHT));
+    pixels
+}
+
+fn draw_scene(renderer: &mut Renderer) {
+    let camera = Camera::new(WIDTH as f32 / HEIGHT as f32);
+    renderer.begin_frame();
+    let frame = na::Matrix4::new_translation(&na::Vector3::new(0.3, 0.2, 0.1))
+        * na::Matrix4::from_axis_angle(&na::Vector3::z_axis(), 0.5);
+    renderer.render(&camera, &frame, &AxesStyle::default());
+    renderer.render(&camera, &na::Matrix4::identity(), &AxesStyle::default());
+    renderer.end_frame();
+}
+
+/// Framebuffer of WIDTH x HEIGHT pixels with `samples` samples per pixel, standing in for a window.
+unsafe fn window_framebuffer(samples: i32) -> u32 {
+    let (mut fbo, mut color) = (0, 0);
+    gl::GenFramebuffers(1, &mut fbo);
+    gl::GenRenderbuffers(1, &mut color);
+    gl::BindRenderbuffer(gl::RENDERBUFFER, color);
+    gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, samples, gl::RGBA8, WIDTH as i32, HEIGHT as i32);
+    gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+    gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::RENDERBUFFER, color);
+    assert_eq!(gl::CheckFramebufferStatus(gl::FRAMEBUFFER), gl::FRAMEBUFFER_COMPLETE);
+    fbo
+}
+
+/// Load the reference thumbnail, failing with a hint when it is missing.
+fn reference() -> Vec<f32> {
+    fs::read_to_string(REFERENCE)
+        .unwrap_or_else(|e| panic!("Missing rendering reference {} ({}), run with UPDATE_REFERENCE=1 to write it", REFERENCE, e))
+        .lines()
+        .map(|line| line.parse().unwrap())
+        .collect()
+}
+
+fn assert_matches_reference(cells: &[f32]) {
+    let reference = reference();
+    assert_eq!(reference.len(), cells.len(), "Reference thumbnail has a different size");
+
+    let difference = cells.iter().zip(&reference).map(|(a, b)| (a - b).abs()).sum::<f32>() / cells.len() as f32;
+    assert!(
+        difference <= TOLERANCE,
+        "Rendered image differs from the reference by {:.2} gray levels on average",
+        difference
+    );
+}
+
+/// Average gray level of each CELL x CELL block of the image.
+fn thumbnail(pixels: &[u8]) -> Vec<f32> {
+    let (columns, rows) = (WIDTH / CELL, HEIGHT / CELL);
+    let mut cells = vec![0.0; (columns * rows) as usize];
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let i = ((y * WIDTH + x) * 4) as usize;
+            let gray = (pixels[i] as f32 + pixels[i + 1] as f32 + pixels[i + 2] as f32) / 3.0;
+            cells[((y / CELL) * columns + x / CELL) as usize] += gray / (CELL * CELL) as f32;
+        }
+    }
+    cells
+}
+
+#[test]
+#[ignore = "needs an OpenGL driver"]
+fn test_render_matches_reference() {
+    let cells = thumbnail(&render_scene());
+
+    if std::env::var_os("UPDATE_REFERENCE").is_some() {
+        let text: String = cells.iter().map(|cell| format!("{:.1}\n", cell)).collect();
+        fs::create_dir_all(Path::new(REFERENCE).parent().unwrap()).unwrap();
+        fs::write(REFERENCE, text).unwrap();
+        return;
+    }
+    assert_matches_reference(&cells);
+}
+
+/// A supersampled render presented into a multisampled window, which blits
+/// cannot scale into.
+#[test]
+#[ignore = "needs an OpenGL driver"]
+fn test_render_scale_into_multisampled_window() {
+    let context = HeadlessContext::new().expect("Failed to create a headless OpenGL context");
+    let config = ViewerConfig {
+        width: WIDTH,
+        height: HEIGHT,
+        render_scale: 2.0,
+        msaa_samples: 4,
+        ..ViewerConfig::default()
+    };
+    let mut renderer = unsafe { Renderer::new_in_current_context(&config, |symbol| context.get_proc_address(symbol)) }
+        .expect("Failed to create the renderer");
+
+    let mut pixels = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    unsafe {
+        let (window, resolved) = (window_framebuffer(4), window_framebuffer(0));
+        gl::BindFramebuffer(gl::FRAMEBUFFER, window);
+        draw_scene(&mut renderer);
+        assert_eq!(gl::GetError(), gl::NO_ERROR);
+
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, window);
+        gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, resolved);
+        let (width, height) = (WIDTH as i32, HEIGHT as i32);
+        gl::BlitFramebuffer(0, 0, width, height, 0, 0, width, height, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+        gl::BindFramebuffer(gl::READ_FRAMEBUFFER, resolved);
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+        gl::ReadPixels(0, 0, width, height, gl::RGBA, gl::UNSIGNED_BYTE, pixels.as_mut_ptr() as *mut _);
+    }
+
+    // OpenGL rows start at the bottom of the image
+    let pixels: Vec<u8> = pixels.chunks_exact(WIDTH as usize * 4).rev().flatten().copied().collect();
+    assert_matches_reference(&thumbnail(&pixels));
+}
+
+#[test]
+#[ignore = "needs an OpenGL driver"]
+fn test_headless_viewer_starts_without_display() {
+    std::env::remove_var("DISPLAY");
+    std::env::remove_var("WAYLAND_DISPLAY");
+    let viewer = Viewer::new_headless(WIDTH, HEIGHT);
+    viewer.start().expect("Failed to start the headless viewer");
+    assert!(viewer.is_running());
+    viewer.stop();
+    viewer.wait();
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+const SMOOTHING: f32 = 0.1; // Weight of the newest tick in the moving averages
+const DROPPED_FRAME_BUDGET: Duration = Duration::from_millis(33); // Below 30 FPS
+
+/// Timing of the render loop, as moving averages over recent ticks.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RenderStats {
+    /// Frames drawn per second
+    pub fps: f32,
+    /// Time spent updating and drawing a frame, excluding waits and throttling
+    pub frame_time: Duration,
+    /// Frames that took longer than 33 ms (a 30 FPS budget) to draw
+    pub dropped: u64,
+    /// Time between the last two ticks, unsmoothed
+    pub last_interval: Duration,
+}
+
+impl RenderStats {
+    /// Account for a tick that took `work` to draw, `interval` after the previous one.
+    pub(crate) fn record(&mut self, interval: Duration, work: Duration) {
+        let fps = 1.0 / interval.as_secs_f32().max(f32::EPSILON);
+        self.last_interval = interval;
+        if self.fps == 0.0 {
+            self.fps = fps;
+            self.frame_time = work;
+        } else {
+            self.fps += SMOOTHING * (fps - self.fps);
+            self.frame_time = self.frame_time.mul_f32(1.0 - SMOOTHING) + work.mul_f32(SMOOTHING);
+        }
+        if work > DROPPED_FRAME_BUDGET {
+            self.dropped += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut stats = RenderStats::default();
+        stats.record(Duration::from_millis(10), Duration::from_millis(5));
+        assert!((stats.fps - 100.0).abs() < 1e-3);
+        assert_eq!(stats.frame_time, Duration::from_millis(5));
+
+        stats.record(Duration::from_millis(50), Duration::from_millis(40));
+        assert!(stats.fps < 100.0);
+        assert_eq!(stats.dropped, 1);
+        assert_eq!(stats.last_interval, Duration::from_millis(50));
+    }
+}
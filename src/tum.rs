@@ -0,0 +1,39 @@
+use nalgebra as na;
+use std::io::{self, Write};
+
+use crate::Transform;
+
+/// Write poses in the TUM RGB-D trajectory format, one
+/// `timestamp tx ty tz qx qy qz qw` line per pose.
+pub fn write_tum<W: Write>(mut writer: W, poses: &[(f64, Transform)]) -> io::Result<()> {
+    for (timestamp, transform) in poses {
+        let rotation = na::Rotation3::from_matrix(&transform.fixed_view::<3, 3>(0, 0).into_owned());
+        let quaternion = na::UnitQuaternion::from_rotation_matrix(&rotation);
+        writeln!(
+            writer,
+            "{:.6} {} {} {} {} {} {} {}",
+            timestamp,
+            transform[(0, 3)],
+            transform[(1, 3)],
+            transform[(2, 3)],
+            quaternion.i,
+            quaternion.j,
+            quaternion.k,
+            quaternion.w,
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_tum() {
+        let transform = na::Matrix4::new_translation(&na::Vector3::new(1.0, 2.0, 3.0));
+        let mut output = Vec::new();
+        write_tum(&mut output, &[(0.5, transform)]).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "0.500000 1 2 3 0 0 0 1\n");
+    }
+}